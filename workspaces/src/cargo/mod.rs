@@ -1,42 +1,458 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
 use crate::error::ErrorKind;
+use crate::types::ContractAbi;
+use crate::CryptoHash;
+
+/// Directory within the target directory of a project where compiled contracts are cached.
+const CACHE_DIR: &str = "near-workspaces";
 
 /// Builds the cargo project located at `project_path` and returns the generated wasm file contents.
 ///
 /// NOTE: This function does not check whether the resulting wasm file is a valid smart
 /// contract or not.
-/// NOTE: This function builds the project using default features
+/// NOTE: This function builds the project using default features. Use [`CompileOptions`]
+/// to build with other options.
 pub async fn compile_project(project_path: &str) -> crate::Result<Vec<u8>> {
-    let project_path = std::fs::canonicalize(project_path).map_err(|e| match e.kind() {
-        std::io::ErrorKind::NotFound => ErrorKind::Io.message(format!(
-            "Incorrect file supplied to compile_project('{}')",
-            project_path
-        )),
-        _ => ErrorKind::Io.custom(e),
-    })?;
-
-    // `no_abi` has become flipped true -> false
-    let cargo_opts = cargo_near_build::BuildOpts {
-        no_locked: true,
-        manifest_path: Some(
-            cargo_near_build::camino::Utf8PathBuf::from_path_buf(project_path.join("Cargo.toml"))
-                .map_err(|error_path| {
-                ErrorKind::Io.custom(format!(
-                    "Unable to construct UTF-8 path from: {}",
-                    error_path.display()
-                ))
-            })?,
-        ),
-        ..Default::default()
+    Ok(CompileOptions::new(project_path).compile().await?.wasm)
+}
+
+/// Options to build a contract project with, through [`CompileOptions::compile`]:
+///
+/// ```ignore
+/// let contract = CompileOptions::new("./contracts")
+///     .package("token")
+///     .features(["testing"])
+///     .compile()
+///     .await?;
+/// let contract = worker.dev_deploy(&contract.wasm).await?;
+/// ```
+///
+/// The resulting wasm is cached within the target directory of the project, keyed by these
+/// options and a hash of the sources of the project along with its path dependencies, so that
+/// repeated runs over the same sources do not recompile the contract. Only the latest build of
+/// each set of options is kept.
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    project_path: PathBuf,
+    package: Option<String>,
+    features: Vec<String>,
+    no_default_features: bool,
+    release: bool,
+    abi: bool,
+    out_dir: Option<PathBuf>,
+    cache: bool,
+}
+
+impl CompileOptions {
+    /// Build the cargo project located at `project_path`, which is either a package or
+    /// a workspace when used together with [`CompileOptions::package`].
+    pub fn new(project_path: impl AsRef<Path>) -> Self {
+        Self {
+            project_path: project_path.as_ref().to_path_buf(),
+            package: None,
+            features: Vec::new(),
+            no_default_features: false,
+            release: true,
+            abi: true,
+            out_dir: None,
+            cache: true,
+        }
+    }
+
+    /// Build the package named `name` out of the workspace at the project path.
+    pub fn package(mut self, name: &str) -> Self {
+        self.package = Some(name.into());
+        self
+    }
+
+    /// Activate the given features of the package.
+    pub fn features<I, S>(mut self, features: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.features.extend(features.into_iter().map(Into::into));
+        self
+    }
+
+    /// Do not activate the default features of the package.
+    pub fn no_default_features(mut self) -> Self {
+        self.no_default_features = true;
+        self
+    }
+
+    /// Build with the debug profile instead of the release one.
+    pub fn debug(mut self) -> Self {
+        self.release = false;
+        self
+    }
+
+    /// Whether to generate the ABI of the contract along with its wasm. Enabled by default,
+    /// which requires the contract to enable the `abi` feature of near-sdk.
+    pub fn abi(mut self, abi: bool) -> Self {
+        self.abi = abi;
+        self
+    }
+
+    /// Copy the build artifacts into `out_dir`, instead of leaving them in the target
+    /// directory of the project only.
+    pub fn out_dir(mut self, out_dir: impl AsRef<Path>) -> Self {
+        self.out_dir = Some(out_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Whether to reuse the wasm of an earlier build of the same sources. Enabled by default.
+    pub fn cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Build the project, or grab it out of the cache if the sources have not changed since
+    /// the last build with the same options.
+    ///
+    /// NOTE: This function does not check whether the resulting wasm file is a valid smart
+    /// contract or not.
+    pub async fn compile(self) -> crate::Result<CompiledContract> {
+        let project_path =
+            std::fs::canonicalize(&self.project_path).map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => ErrorKind::Io.message(format!(
+                    "no cargo project found at '{}'",
+                    self.project_path.display()
+                )),
+                _ => ErrorKind::Io.custom(e),
+            })?;
+        let manifest_path = match &self.package {
+            Some(package) => package_manifest(&project_path, package)?,
+            None => project_path.join("Cargo.toml"),
+        };
+
+        // Every set of options gets its own directory in the cache, holding the artifacts of
+        // its latest build under the same names cargo-near gave them.
+        let options_dir = project_path
+            .join("target")
+            .join(CACHE_DIR)
+            .join(self.options_key());
+        let cache_dir = options_dir.join(sources_key(&project_path)?);
+
+        if self.cache {
+            if let Some(cached_wasm) = find_wasm(&cache_dir)? {
+                tracing::debug!(
+                    target: "workspaces",
+                    "using cached build {} of {}",
+                    cached_wasm.display(),
+                    manifest_path.display()
+                );
+                let compiled =
+                    CompiledContract::read(&cached_wasm, Some(abi_path_of(&cached_wasm).as_path()))
+                        .await?;
+                if let Some(out_dir) = &self.out_dir {
+                    compiled.write_to(out_dir).await?;
+                }
+                return Ok(compiled);
+            }
+        }
+
+        let wasm_path = self.build(&manifest_path)?;
+        let abi_path = self.abi.then(|| abi_path_of(&wasm_path));
+        let compiled = CompiledContract::read(&wasm_path, abi_path.as_deref()).await?;
+
+        if self.cache {
+            tokio::fs::create_dir_all(&cache_dir)
+                .await
+                .map_err(|e| ErrorKind::Io.custom(e))?;
+            compiled.write_to(&cache_dir).await?;
+            prune_cache(&options_dir, &cache_dir)?;
+        }
+        Ok(compiled)
+    }
+
+    /// Build the package at `manifest_path` through cargo-near, returning the path of the
+    /// resulting wasm file.
+    fn build(&self, manifest_path: &Path) -> crate::Result<PathBuf> {
+        let utf8_path = |path: &Path| {
+            cargo_near_build::camino::Utf8PathBuf::from_path_buf(path.to_path_buf()).map_err(
+                |error_path| {
+                    ErrorKind::Io.custom(format!(
+                        "Unable to construct UTF-8 path from: {}",
+                        error_path.display()
+                    ))
+                },
+            )
+        };
+
+        // `no_abi` has become flipped true -> false
+        let cargo_opts = cargo_near_build::BuildOpts {
+            no_locked: true,
+            no_release: !self.release,
+            no_abi: !self.abi,
+            no_default_features: self.no_default_features,
+            features: (!self.features.is_empty()).then(|| self.features.join(",")),
+            out_dir: self.out_dir.as_deref().map(utf8_path).transpose()?,
+            manifest_path: Some(utf8_path(manifest_path)?),
+            ..Default::default()
+        };
+
+        let compile_artifact =
+            cargo_near_build::build(cargo_opts).map_err(|e| ErrorKind::Io.custom(e))?;
+
+        compile_artifact
+            .path
+            .canonicalize()
+            .map_err(|e| ErrorKind::Io.custom(e))
+    }
+
+    /// Hash of the options affecting the build output.
+    fn options_key(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(format!(
+            "{:?}|{:?}|{}|{}|{}",
+            self.package, self.features, self.no_default_features, self.release, self.abi
+        ));
+        hex(&hasher.finalize())
+    }
+}
+
+/// A contract built through [`CompileOptions::compile`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CompiledContract {
+    /// Bytes of the wasm file, ready to be deployed.
+    pub wasm: Vec<u8>,
+    /// ABI of the contract, if it got generated.
+    pub abi: Option<ContractAbi>,
+    /// Hash of the wasm, the same as the `code_hash` of an account the contract is
+    /// deployed to.
+    pub code_hash: CryptoHash,
+    /// Raw JSON of the ABI, kept to write it out along with the wasm.
+    abi_json: Option<Vec<u8>>,
+    /// Name of the wasm file, as given by cargo-near.
+    file_name: PathBuf,
+}
+
+impl CompiledContract {
+    async fn read(wasm_path: &Path, abi_path: Option<&Path>) -> crate::Result<Self> {
+        let wasm = tokio::fs::read(wasm_path)
+            .await
+            .map_err(|e| ErrorKind::Io.custom(e))?;
+        let abi_json = match abi_path {
+            Some(path) if path.is_file() => Some(
+                tokio::fs::read(path)
+                    .await
+                    .map_err(|e| ErrorKind::Io.custom(e))?,
+            ),
+            _ => None,
+        };
+        let abi = abi_json
+            .as_deref()
+            .map(ContractAbi::from_slice)
+            .transpose()?;
+
+        Ok(Self {
+            code_hash: CryptoHash::hash_bytes(&wasm),
+            wasm,
+            abi,
+            abi_json,
+            file_name: wasm_path.file_name().unwrap_or_default().into(),
+        })
+    }
+
+    /// Write the wasm, along with the ABI if there is one, into `dir`. The ABI is written
+    /// first, so that anyone finding the wasm in `dir` also finds its ABI.
+    async fn write_to(&self, dir: &Path) -> crate::Result<()> {
+        let wasm_path = dir.join(&self.file_name);
+        if let Some(abi) = &self.abi_json {
+            write_atomic(&abi_path_of(&wasm_path), abi).await?;
+        }
+        write_atomic(&wasm_path, &self.wasm).await
+    }
+}
+
+/// Write `contents` to `path` through a temporary file in the same directory, so that
+/// concurrent readers never observe a partially written file.
+async fn write_atomic(path: &Path, contents: &[u8]) -> crate::Result<()> {
+    let path = path.to_path_buf();
+    let contents = contents.to_vec();
+    tokio::task::spawn_blocking(move || {
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut file = tempfile::Builder::new()
+            .prefix(".tmp")
+            .tempfile_in(dir)
+            .map_err(|e| ErrorKind::Io.custom(e))?;
+        std::io::Write::write_all(&mut file, &contents).map_err(|e| ErrorKind::Io.custom(e))?;
+        file.persist(&path)
+            .map_err(|e| ErrorKind::Io.custom(e.error))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| ErrorKind::Io.custom(e))?
+}
+
+/// Remove every build cached in `options_dir` other than the one in `keep`, as they were built
+/// out of sources which have since changed.
+fn prune_cache(options_dir: &Path, keep: &Path) -> crate::Result<()> {
+    let entries = std::fs::read_dir(options_dir).map_err(|e| ErrorKind::Io.custom(e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| ErrorKind::Io.custom(e))?.path();
+        if path == keep || !path.is_dir() {
+            continue;
+        }
+        match std::fs::remove_dir_all(&path) {
+            Ok(()) => {}
+            // Another build pruning the same cache got to it first.
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(ErrorKind::Io.custom(err)),
+        }
+    }
+    Ok(())
+}
+
+/// Path of the ABI cargo-near generates next to `wasm_path`, as `<name>_abi.json`.
+fn abi_path_of(wasm_path: &Path) -> PathBuf {
+    let stem = wasm_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    wasm_path.with_file_name(format!("{stem}_abi.json"))
+}
+
+/// Find the wasm file of a cached build, if there is one.
+fn find_wasm(cache_dir: &Path) -> crate::Result<Option<PathBuf>> {
+    let entries = match std::fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(ErrorKind::Io.custom(err)),
     };
 
-    let compile_artifact =
-        cargo_near_build::build(cargo_opts).map_err(|e| ErrorKind::Io.custom(e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| ErrorKind::Io.custom(e))?.path();
+        if path.extension().is_some_and(|ext| ext == "wasm") {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+/// Run `cargo metadata` over the project at `project_path`, without resolving dependencies.
+fn cargo_metadata(project_path: &Path) -> crate::Result<serde_json::Value> {
+    let output = std::process::Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(project_path)
+        .output()
+        .map_err(|e| ErrorKind::Io.full("failed to run `cargo metadata`", e))?;
+    if !output.status.success() {
+        return Err(ErrorKind::Io.message(format!(
+            "`cargo metadata` failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
 
-    let file = compile_artifact
-        .path
-        .canonicalize()
+    serde_json::from_slice(&output.stdout).map_err(|e| ErrorKind::DataConversion.custom(e))
+}
+
+/// Find the manifest of the workspace member named `package` through `cargo metadata`.
+fn package_manifest(project_path: &Path, package: &str) -> crate::Result<PathBuf> {
+    let metadata = cargo_metadata(project_path)?;
+    metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|pkg| pkg["name"] == package)
+        .and_then(|pkg| pkg["manifest_path"].as_str())
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            ErrorKind::Io.message(format!(
+                "no package named {package} in {}",
+                project_path.display()
+            ))
+        })
+}
+
+/// Hash of the sources of the project at `project_path`, along with those of the path
+/// dependencies living outside of it.
+fn sources_key(project_path: &Path) -> crate::Result<String> {
+    let mut hasher = Sha256::new();
+    hash_sources(project_path, project_path, &mut hasher)?;
+    for dependency in external_path_dependencies(project_path)? {
+        hasher.update(dependency.to_string_lossy().as_bytes());
+        hash_sources(&dependency, &dependency, &mut hasher)?;
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Directories of the path dependencies of the project which live outside of `project_path`,
+/// including those of the path dependencies themselves.
+fn external_path_dependencies(project_path: &Path) -> crate::Result<BTreeSet<PathBuf>> {
+    let mut visited = BTreeSet::from([project_path.to_path_buf()]);
+    let mut pending = vec![project_path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let metadata = cargo_metadata(&dir)?;
+        let paths = metadata["packages"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            // Only the packages within `dir` matter, rather than every member of the
+            // workspace it belongs to.
+            .filter(|pkg| {
+                pkg["manifest_path"]
+                    .as_str()
+                    .is_some_and(|manifest| Path::new(manifest).starts_with(&dir))
+            })
+            .flat_map(|pkg| pkg["dependencies"].as_array().into_iter().flatten())
+            .filter_map(|dep| dep["path"].as_str())
+            .filter_map(|path| std::fs::canonicalize(path).ok())
+            .collect::<Vec<_>>();
+
+        for path in paths {
+            if visited.insert(path.clone()) {
+                pending.push(path);
+            }
+        }
+    }
+
+    // Changes to the dependencies within the project are already picked up by its hash.
+    Ok(visited
+        .into_iter()
+        .filter(|path| !path.starts_with(project_path))
+        .collect())
+}
+
+/// Hash every file of the project, skipping build outputs and hidden directories, in a
+/// stable order.
+fn hash_sources(root: &Path, dir: &Path, hasher: &mut Sha256) -> crate::Result<()> {
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(|e| ErrorKind::Io.custom(e))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
         .map_err(|e| ErrorKind::Io.custom(e))?;
-    tokio::fs::read(file)
-        .await
-        .map_err(|e| ErrorKind::Io.custom(e))
+    entries.sort();
+
+    for path in entries {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with('.') || (path.is_dir() && name == "target") {
+            continue;
+        }
+
+        if path.is_dir() {
+            hash_sources(root, &path, hasher)?;
+        } else {
+            let contents = std::fs::read(&path).map_err(|e| ErrorKind::Io.custom(e))?;
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update((contents.len() as u64).to_le_bytes());
+            hasher.update(&contents);
+        }
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
 }
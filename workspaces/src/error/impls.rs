@@ -3,7 +3,11 @@ use std::{borrow::Cow, sync::PoisonError};
 
 use crate::result::ExecutionFailure;
 
-use super::{Error, ErrorKind, ErrorRepr, RpcErrorCode, SandboxErrorCode};
+use near_jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use near_jsonrpc_primitives::types::query::RpcQueryError;
+use near_primitives::errors::InvalidAccessKeyError;
+
+use super::{AccessKeyErrorCode, Error, ErrorKind, ErrorRepr, RpcErrorCode, SandboxErrorCode};
 
 impl ErrorKind {
     pub(crate) fn custom<E>(self, error: E) -> Error
@@ -121,6 +125,35 @@ impl Error {
             _ => Err(self),
         }
     }
+
+    /// Whether this is the error of a query made against an account that does not exist.
+    pub(crate) fn is_unknown_account(&self) -> bool {
+        let error = match &self.repr {
+            ErrorRepr::Custom { error, .. } | ErrorRepr::Full { error, .. } => error,
+            _ => return false,
+        };
+        matches!(
+            error.downcast_ref::<JsonRpcError<RpcQueryError>>(),
+            Some(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                RpcQueryError::UnknownAccount { .. }
+            )))
+        )
+    }
+
+    /// Whether this is the error of a view call into a method the contract does not have.
+    pub(crate) fn is_method_not_found(&self) -> bool {
+        let error = match &self.repr {
+            ErrorRepr::Custom { error, .. } | ErrorRepr::Full { error, .. } => error,
+            _ => return false,
+        };
+        // The node only reports the error of the contract as a debug formatted string.
+        matches!(
+            error.downcast_ref::<JsonRpcError<RpcQueryError>>(),
+            Some(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+                RpcQueryError::ContractExecutionError { vm_error, .. }
+            ))) if vm_error.contains("MethodResolveError(MethodNotFound)")
+        )
+    }
 }
 
 impl fmt::Display for Error {
@@ -192,3 +225,31 @@ impl From<RpcErrorCode> for Error {
         Self::simple(ErrorKind::Rpc(code))
     }
 }
+
+impl AccessKeyErrorCode {
+    pub(crate) fn custom<E>(self, error: E) -> Error
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Error::custom(ErrorKind::AccessKey(self), error)
+    }
+}
+
+impl From<&InvalidAccessKeyError> for AccessKeyErrorCode {
+    fn from(error: &InvalidAccessKeyError) -> Self {
+        match error {
+            InvalidAccessKeyError::AccessKeyNotFound { .. } => Self::NotFound,
+            InvalidAccessKeyError::ReceiverMismatch { .. } => Self::ReceiverMismatch,
+            InvalidAccessKeyError::MethodNameMismatch { .. } => Self::MethodNameMismatch,
+            InvalidAccessKeyError::RequiresFullAccess => Self::RequiresFullAccess,
+            InvalidAccessKeyError::NotEnoughAllowance { .. } => Self::NotEnoughAllowance,
+            InvalidAccessKeyError::DepositWithFunctionCall => Self::DepositWithFunctionCall,
+        }
+    }
+}
+
+impl From<AccessKeyErrorCode> for Error {
+    fn from(code: AccessKeyErrorCode) -> Self {
+        Self::simple(ErrorKind::AccessKey(code))
+    }
+}
@@ -27,6 +27,29 @@ pub enum ErrorKind {
     /// An error from converting data.
     #[error("DataConversion")]
     DataConversion,
+    /// An error from signing a transaction with an access key that is not permitted
+    /// to perform it.
+    #[error("{0}")]
+    AccessKey(#[from] AccessKeyErrorCode),
+    /// An error from gas usage regressing against a baseline of [`GasSnapshot`].
+    ///
+    /// [`GasSnapshot`]: crate::types::GasSnapshot
+    #[error("GasRegression")]
+    GasRegression,
+    /// An error from attempting to send a transaction to a network in read-only mode,
+    /// which is the default for mainnet.
+    #[error("ReadOnly")]
+    ReadOnly,
+    /// An error from loading the ABI of a contract, or from a call that does not match it.
+    #[error("Abi")]
+    Abi,
+    /// An error from parsing contract code, or from it breaking the rules of the runtime.
+    #[error("Wasm")]
+    Wasm,
+    /// An error from using the API in a way it does not support, such as combining options
+    /// that cannot be used together.
+    #[error("Usage")]
+    Usage,
     /// An error that cannot be categorized into the other error kinds.
     #[error("Other")]
     Other,
@@ -84,6 +107,23 @@ pub enum SandboxErrorCode {
     FastForwardFailure,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum AccessKeyErrorCode {
+    #[error("access key used to sign the transaction does not exist")]
+    NotFound,
+    #[error("transaction receiver does not match the receiver allowed by the access key")]
+    ReceiverMismatch,
+    #[error("method is not allowed by the access key")]
+    MethodNameMismatch,
+    #[error("transaction requires a full access key")]
+    RequiresFullAccess,
+    #[error("access key does not have enough allowance to cover the transaction")]
+    NotEnoughAllowance,
+    #[error("function call access keys cannot attach a deposit")]
+    DepositWithFunctionCall,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
 #[non_exhaustive]
 pub enum RpcErrorCode {
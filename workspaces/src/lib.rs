@@ -9,7 +9,7 @@
 #[cfg(feature = "unstable")]
 mod cargo;
 #[cfg(feature = "unstable")]
-pub use cargo::compile_project;
+pub use cargo::{compile_project, CompileOptions, CompiledContract};
 #[cfg(feature = "unstable")]
 pub use cargo_near_build;
 
@@ -21,10 +21,12 @@ pub mod operations;
 pub mod prelude;
 pub mod result;
 pub mod rpc;
+pub mod tokens;
 pub mod types;
 
 /// The near_abi_client implementation is currently in flux and we offer a re-export
-/// of it and example code. No public near_abi APIs are baked into workspace-rs yet.
+/// of it and example code. For checking calls against the ABI of a deployed contract,
+/// see [`Contract::abi`] and [`Contract::with_abi`].
 pub use near_abi_client;
 
 pub use network::pick_unused_port;
@@ -37,7 +39,7 @@ pub use types::{AccessKey, AccountId, BlockHeight, CryptoHash, InMemorySigner};
 pub use worker::{
     betanet, mainnet, mainnet_archival, sandbox, sandbox_with_version, testnet, testnet_archival,
     with_betanet, with_mainnet, with_mainnet_archival, with_sandbox, with_testnet,
-    with_testnet_archival, Worker,
+    with_testnet_archival, CleanupGuard, CleanupReport, Worker,
 };
 
 #[cfg(feature = "unstable")]
@@ -38,7 +38,9 @@ impl FromNetworkBuilder for Betanet {
             info: Info {
                 name: build.name.into(),
                 root_id: "near".parse().unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/betanet/"),
+                keystore_path: build
+                    .keystore_path
+                    .unwrap_or_else(|| PathBuf::from(".near-credentials/betanet/")),
                 rpc_url: Url::parse(&rpc_url).expect("url is hardcoded"),
             },
         })
@@ -1,7 +1,9 @@
 use std::future::{Future, IntoFuture};
 use std::marker::PhantomData;
+use std::path::PathBuf;
 
-use crate::network::Sandbox;
+use crate::network::{Mainnet, Sandbox, Testnet};
+use crate::types::{InMemorySigner, NearToken};
 use crate::{Network, Worker};
 
 use super::server::ValidatorKey;
@@ -30,6 +32,10 @@ pub struct NetworkBuilder<'a, T> {
     pub(crate) rpc_addr: Option<String>,
     pub(crate) validator_key: Option<ValidatorKey>,
     pub(crate) api_key: Option<String>,
+    pub(crate) sponsor: Option<InMemorySigner>,
+    pub(crate) sponsor_initial_balance: Option<NearToken>,
+    pub(crate) allow_transactions: bool,
+    pub(crate) keystore_path: Option<PathBuf>,
     _network: PhantomData<T>,
 }
 
@@ -56,6 +62,10 @@ impl<'a, T> NetworkBuilder<'a, T> {
             rpc_addr: None,
             validator_key: None,
             api_key: None,
+            sponsor: None,
+            sponsor_initial_balance: None,
+            allow_transactions: false,
+            keystore_path: None,
             _network: PhantomData,
         }
     }
@@ -81,6 +91,15 @@ impl<'a, T> NetworkBuilder<'a, T> {
         self.api_key = Some(api_key.into());
         self
     }
+
+    /// Sets the keystore directory credentials are loaded from by
+    /// [`Worker::account_from_keystore`]. Defaults to `.near-credentials/<network>/`.
+    ///
+    /// [`Worker::account_from_keystore`]: crate::Worker::account_from_keystore
+    pub fn keystore_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.keystore_path = Some(path.into());
+        self
+    }
 }
 
 // So far, only Sandbox makes use of validator_key.
@@ -95,3 +114,39 @@ impl NetworkBuilder<'_, Sandbox> {
         self
     }
 }
+
+// So far, only Testnet makes use of a sponsor.
+impl NetworkBuilder<'_, Testnet> {
+    /// Create dev accounts as subaccounts of our own funded testnet account, instead of
+    /// going through the rate limited helper service. Accounts such as the ones created by
+    /// [`Worker::dev_create_account`] and [`Worker::dev_deploy`] become `<random>.<sponsor>`,
+    /// created and funded through a `CreateAccount` transaction signed by the sponsor.
+    ///
+    /// [`Worker::dev_create_account`]: crate::Worker::dev_create_account
+    /// [`Worker::dev_deploy`]: crate::Worker::dev_deploy
+    pub fn sponsor(mut self, signer: InMemorySigner) -> Self {
+        self.sponsor = Some(signer);
+        self
+    }
+
+    /// Sets the balance the sponsor funds every created dev account with. Defaults to 10 NEAR,
+    /// the same as accounts created through the helper service. Requires a
+    /// [`sponsor`](Self::sponsor), as building the worker fails otherwise.
+    pub fn sponsor_initial_balance(mut self, balance: NearToken) -> Self {
+        self.sponsor_initial_balance = Some(balance);
+        self
+    }
+}
+
+// So far, only Mainnet is read-only by default.
+impl NetworkBuilder<'_, Mainnet> {
+    /// Allow sending transactions to mainnet. Without this, the worker is read-only and any
+    /// attempt to send a transaction fails with [`ErrorKind::ReadOnly`], to guard against
+    /// accidentally spending real tokens.
+    ///
+    /// [`ErrorKind::ReadOnly`]: crate::error::ErrorKind::ReadOnly
+    pub fn allow_transactions(mut self) -> Self {
+        self.allow_transactions = true;
+        self
+    }
+}
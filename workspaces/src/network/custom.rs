@@ -25,7 +25,9 @@ impl FromNetworkBuilder for Custom {
             info: Info {
                 name: build.name.into(),
                 root_id: "near".parse().unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/mainnet/"),
+                keystore_path: build
+                    .keystore_path
+                    .unwrap_or_else(|| PathBuf::from(".near-credentials/mainnet/")),
                 rpc_url: url::Url::parse(&rpc_url).expect("custom provided url should be valid"),
             },
         })
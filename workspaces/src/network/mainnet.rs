@@ -17,7 +17,8 @@ pub const ARCHIVAL_URL: &str = "https://archival-rpc.mainnet.near.org";
 /// spin up a [`Worker`] that can be used to interact with mainnet. Note that
 /// mainnet account creation is not currently supported, and these calls into
 /// creating a mainnet worker is meant for retrieving data and/or making
-/// queries only.
+/// queries only. Sending transactions is refused unless explicitly allowed through
+/// [`NetworkBuilder::allow_transactions`].
 ///
 /// [`workspaces::mainnet`]: crate::mainnet
 /// [`workspaces::mainnet_archival`]: crate::mainnet_archival
@@ -31,7 +32,7 @@ pub struct Mainnet {
 impl FromNetworkBuilder for Mainnet {
     async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
         let rpc_url = build.rpc_addr.unwrap_or_else(|| RPC_URL.into());
-        let client = Client::new(&rpc_url, build.api_key)?;
+        let client = Client::new(&rpc_url, build.api_key)?.read_only(!build.allow_transactions);
         client.wait_for_rpc().await?;
 
         Ok(Self {
@@ -39,7 +40,9 @@ impl FromNetworkBuilder for Mainnet {
             info: Info {
                 name: build.name.into(),
                 root_id: "near".parse().unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/mainnet/"),
+                keystore_path: build
+                    .keystore_path
+                    .unwrap_or_else(|| PathBuf::from(".near-credentials/mainnet/")),
                 rpc_url: url::Url::parse(&rpc_url).expect("url is hardcoded"),
             },
         })
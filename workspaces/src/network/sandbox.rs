@@ -14,7 +14,7 @@ use crate::error::SandboxErrorCode;
 use crate::network::server::SandboxServer;
 use crate::network::Info;
 use crate::result::{Execution, ExecutionFinalResult, Result};
-use crate::rpc::client::Client;
+use crate::rpc::client::{Client, DEFAULT_WAIT_UNTIL};
 use crate::types::{AccountId, InMemorySigner, NearToken, SecretKey};
 use crate::{Account, Contract, Network, Worker};
 
@@ -91,7 +91,9 @@ impl Sandbox {
         let info = Info {
             name: build.name.into(),
             root_id,
-            keystore_path: PathBuf::from(".near-credentials/sandbox/"),
+            keystore_path: build
+                .keystore_path
+                .unwrap_or_else(|| PathBuf::from(".near-credentials/sandbox/")),
             rpc_url: url::Url::parse(&server.rpc_addr()).expect("url is hardcoded"),
         };
 
@@ -134,7 +136,13 @@ impl TopLevelAccountCreator for Sandbox {
         let root_signer = self.registrar_signer()?;
         let outcome = self
             .client()
-            .create_account(&root_signer, &id, sk.public_key(), DEFAULT_DEPOSIT)
+            .create_account(
+                &root_signer,
+                &id,
+                sk.public_key(),
+                DEFAULT_DEPOSIT,
+                DEFAULT_WAIT_UNTIL,
+            )
             .await?;
         let signer = InMemorySigner::from_secret_key(id, sk);
         Ok(Execution {
@@ -185,7 +193,13 @@ impl RootAccountSubaccountCreator for Sandbox {
         let root_signer = self.root_signer()?;
         let outcome = self
             .client()
-            .create_account(&root_signer, &id, sk.public_key(), DEFAULT_DEPOSIT)
+            .create_account(
+                &root_signer,
+                &id,
+                sk.public_key(),
+                DEFAULT_DEPOSIT,
+                DEFAULT_WAIT_UNTIL,
+            )
             .await?;
         let signer = InMemorySigner::from_secret_key(id, sk);
         Ok(Execution {
@@ -230,6 +244,10 @@ impl NetworkInfo for Sandbox {
     fn info(&self) -> &Info {
         &self.info
     }
+
+    fn is_sandbox(&self) -> bool {
+        true
+    }
 }
 
 impl Sandbox {
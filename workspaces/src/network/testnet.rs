@@ -7,11 +7,13 @@ use url::Url;
 
 use near_primitives::views::ExecutionStatusView;
 
+use crate::error::ErrorKind;
 use crate::network::builder::{FromNetworkBuilder, NetworkBuilder};
 use crate::network::Info;
 use crate::network::{NetworkClient, NetworkInfo, RootAccountSubaccountCreator};
 use crate::result::{Execution, ExecutionDetails, ExecutionFinalResult, ExecutionOutcome, Result};
-use crate::rpc::{client::Client, tool};
+use crate::rpc::client::{Client, DEFAULT_WAIT_UNTIL};
+use crate::rpc::tool;
 use crate::types::{AccountId, InMemorySigner, NearToken, SecretKey};
 use crate::{Account, Contract, CryptoHash, Network, Worker};
 
@@ -24,6 +26,9 @@ pub const HELPER_URL: &str = "https://helper.testnet.near.org";
 /// URL to the testnet archival RPC node provided by near.org.
 pub const ARCHIVAL_URL: &str = "https://archival-rpc.testnet.near.org";
 
+/// Balance a sponsor funds dev accounts with, unless specified otherwise.
+const DEFAULT_SPONSOR_INITIAL_BALANCE: NearToken = NearToken::from_near(10);
+
 /// Testnet related configuration for interacting with testnet.
 ///
 /// Look at [`workspaces::testnet`] and [`workspaces::testnet_archival`] for how
@@ -35,23 +40,49 @@ pub const ARCHIVAL_URL: &str = "https://archival-rpc.testnet.near.org";
 pub struct Testnet {
     client: Client,
     info: Info,
+    sponsor: Option<Sponsor>,
+}
+
+/// Funded account that creates dev accounts as its subaccounts. See
+/// [`NetworkBuilder::sponsor`] for how to set it.
+struct Sponsor {
+    signer: InMemorySigner,
+    initial_balance: NearToken,
 }
 
 #[async_trait]
 impl FromNetworkBuilder for Testnet {
     async fn from_builder<'a>(build: NetworkBuilder<'a, Self>) -> Result<Self> {
+        if build.sponsor.is_none() && build.sponsor_initial_balance.is_some() {
+            return Err(ErrorKind::Usage.message(
+                "`sponsor_initial_balance` requires a `sponsor`, as accounts created through \
+                 the helper service are always funded with the same balance",
+            ));
+        }
+
         let rpc_url = build.rpc_addr.unwrap_or_else(|| RPC_URL.into());
         let client = Client::new(&rpc_url, build.api_key)?;
         client.wait_for_rpc().await?;
 
+        let initial_balance = build
+            .sponsor_initial_balance
+            .unwrap_or(DEFAULT_SPONSOR_INITIAL_BALANCE);
+        let sponsor = build.sponsor.map(|signer| Sponsor {
+            signer,
+            initial_balance,
+        });
+
         Ok(Self {
             client,
             info: Info {
                 name: build.name.into(),
                 root_id: AccountId::from_str("testnet").unwrap(),
-                keystore_path: PathBuf::from(".near-credentials/testnet/"),
+                keystore_path: build
+                    .keystore_path
+                    .unwrap_or_else(|| PathBuf::from(".near-credentials/testnet/")),
                 rpc_url: Url::parse(&rpc_url).expect("url is hardcoded"),
             },
+            sponsor,
         })
     }
 }
@@ -61,14 +92,25 @@ impl std::fmt::Debug for Testnet {
         f.debug_struct("Testnet")
             .field("root_id", &self.info.root_id)
             .field("rpc_url", &self.info.rpc_url)
+            .field(
+                "sponsor",
+                &self
+                    .sponsor
+                    .as_ref()
+                    .map(|sponsor| &sponsor.signer.account_id),
+            )
             .finish()
     }
 }
 
 #[async_trait]
 impl RootAccountSubaccountCreator for Testnet {
+    /// The sponsor account if one was set, otherwise the `testnet` registrar.
     fn root_account_id(&self) -> Result<AccountId> {
-        Ok(self.info().root_id.clone())
+        match &self.sponsor {
+            Some(sponsor) => Ok(sponsor.signer.account_id.clone()),
+            None => Ok(self.info().root_id.clone()),
+        }
     }
 
     async fn create_root_account_subaccount(
@@ -79,6 +121,25 @@ impl RootAccountSubaccountCreator for Testnet {
         // TODO: return Account only, but then you don't get metadata info for it...
     ) -> Result<Execution<Account>> {
         let id = self.compute_subaccount_id(subaccount_prefix)?;
+        if let Some(sponsor) = &self.sponsor {
+            let outcome = self
+                .client
+                .create_account(
+                    &sponsor.signer,
+                    &id,
+                    sk.public_key(),
+                    sponsor.initial_balance,
+                    DEFAULT_WAIT_UNTIL,
+                )
+                .await?;
+            let signer = InMemorySigner::from_secret_key(id, sk);
+
+            return Ok(Execution {
+                result: Account::new(signer, worker),
+                details: ExecutionFinalResult::from_view(outcome),
+            });
+        }
+
         let url = Url::parse(HELPER_URL).unwrap();
         //only registrar can create tla on testnet, so must concatenate random created id with .testnet
         tool::url_create_account(url, id.clone(), sk.public_key()).await?;
@@ -101,10 +162,15 @@ impl RootAccountSubaccountCreator for Testnet {
                         gas_burnt: NearGas::from_gas(0),
                         tokens_burnt: NearToken::from_near(0),
                         executor_id: "testnet".parse().unwrap(),
+                        gas_profile: None,
                         status: ExecutionStatusView::SuccessValue(Vec::new()),
                     },
                     receipts: Vec::new(),
+                    signed_transaction: None,
+                    receipt_views: Vec::new(),
                 },
+                allowance_used: None,
+                balance_diff: None,
             },
         })
     }
@@ -116,6 +182,26 @@ impl RootAccountSubaccountCreator for Testnet {
         sk: SecretKey,
         wasm: &[u8],
     ) -> Result<Execution<Contract>> {
+        if let Some(sponsor) = &self.sponsor {
+            let id = self.compute_subaccount_id(subaccount_prefix)?;
+            let outcome = self
+                .client
+                .create_account_and_deploy(
+                    &sponsor.signer,
+                    &id,
+                    sk.public_key(),
+                    sponsor.initial_balance,
+                    wasm.to_vec(),
+                )
+                .await?;
+            let signer = InMemorySigner::from_secret_key(id, sk);
+
+            return Ok(Execution {
+                result: Contract::new(signer, worker),
+                details: ExecutionFinalResult::from_view(outcome),
+            });
+        }
+
         let account = self
             .create_root_account_subaccount(worker, subaccount_prefix.clone(), sk)
             .await?;
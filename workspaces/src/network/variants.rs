@@ -4,7 +4,7 @@ use crate::error::ErrorKind;
 use crate::network::Info;
 use crate::result::{Execution, Result};
 use crate::rpc::client::Client;
-use crate::types::{AccountId, KeyType, SecretKey};
+use crate::types::{AccountId, ContractCode, KeyType, SecretKey};
 use crate::{Account, Contract, Worker};
 use async_trait::async_trait;
 
@@ -16,6 +16,11 @@ pub trait NetworkClient {
 
 pub trait NetworkInfo {
     fn info(&self) -> &Info;
+
+    /// Whether this network is a local sandbox, which allows its state to be patched.
+    fn is_sandbox(&self) -> bool {
+        false
+    }
 }
 
 /// Trait provides the ability to create a sponsored subaccount of network's root account.
@@ -99,7 +104,10 @@ where
             .await?;
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
+        }
+        if res.details.is_success() {
+            self.track_account(res.result.signer())?;
         }
 
         Ok(res)
@@ -118,7 +126,10 @@ where
             .await?;
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
+        }
+        if res.details.is_success() {
+            self.track_account(res.result.signer())?;
         }
 
         Ok(res)
@@ -156,7 +167,10 @@ where
             .await?;
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
+        }
+        if res.details.is_success() {
+            self.track_account(res.result.signer())?;
         }
 
         Ok(res)
@@ -179,7 +193,10 @@ where
             .await?;
 
         for callback in self.tx_callbacks.iter() {
-            callback(res.details.total_gas_burnt)?;
+            callback(&res.details)?;
+        }
+        if res.details.is_success() {
+            self.track_account(res.result.signer())?;
         }
 
         Ok(res)
@@ -203,6 +220,14 @@ where
             .await?;
         Ok(contract.into_result()?)
     }
+
+    /// Deploy contract code to a new dev account, like [`Worker::dev_deploy`], but only once
+    /// it passes [`ContractCode::validate`]. Invalid code fails with [`ErrorKind::Wasm`]
+    /// without any account being created.
+    pub async fn dev_deploy_checked(&self, wasm: &[u8]) -> Result<Contract> {
+        ContractCode::parse(wasm)?.validate(self.max_contract_size().await?)?;
+        self.dev_deploy(wasm).await
+    }
 }
 
 /// Network trait specifies the functionality of a network type such as mainnet, testnet or any
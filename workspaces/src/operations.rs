@@ -1,17 +1,22 @@
 //! All operation types that are generated/used when making transactions or view calls.
 
-use crate::error::{ErrorKind, RpcErrorCode};
-use crate::result::{Execution, ExecutionFinalResult, Result, ViewResultDetails};
+use crate::error::{Error, ErrorKind, RpcErrorCode};
+use crate::result::{
+    AccountBalance, AccountBalanceDiff, BalanceDiff, Execution, ExecutionFinalResult, GasEstimate,
+    Result, StateDiff, UpgradeResult, ViewResultDetails,
+};
 use crate::rpc::client::{
-    send_batch_tx_and_retry, send_batch_tx_async_and_retry, DEFAULT_CALL_DEPOSIT,
-    DEFAULT_CALL_FN_GAS,
+    execution_level, send_batch_tx_and_retry, send_batch_tx_async_and_retry, DEFAULT_CALL_DEPOSIT,
+    DEFAULT_CALL_FN_GAS, DEFAULT_WAIT_UNTIL,
 };
-use crate::rpc::query::{Query, ViewFunction};
+use crate::rpc::estimate::estimate;
+use crate::rpc::query::{Query, ViewAccessKey, ViewFunction};
 use crate::types::{
-    AccessKey, AccountId, Gas, InMemorySigner, KeyType, NearToken, PublicKey, SecretKey,
+    AccessKey, AccessKeyPermission, AccountId, ContractAbi, ContractState, Gas, InMemorySigner,
+    KeyType, NearToken, PublicKey, SecretKey,
 };
 use crate::worker::Worker;
-use crate::{Account, CryptoHash, Network};
+use crate::{Account, Contract, CryptoHash, Network};
 
 use near_account_id::ParseAccountError;
 use near_gas::NearGas;
@@ -22,11 +27,14 @@ use near_primitives::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
     DeployContractAction, FunctionCallAction, StakeAction, TransferAction,
 };
-use near_primitives::views::{FinalExecutionOutcomeView, TxExecutionStatus};
+use near_primitives::views::{
+    FinalExecutionOutcomeViewEnum, FinalExecutionOutcomeWithReceiptView, TxExecutionStatus,
+};
 use std::convert::TryInto;
 use std::fmt;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::Poll;
 
 const MAX_GAS: NearGas = NearGas::from_tgas(300);
@@ -122,6 +130,9 @@ pub struct Transaction {
     receiver_id: AccountId,
     // Result used to defer errors in argument parsing to later when calling into transact
     actions: Result<Vec<Action>>,
+    wait_until: TxExecutionStatus,
+    /// Whether to record the balances of touched accounts before and after the transaction.
+    balance_diff: bool,
 }
 
 impl Transaction {
@@ -135,6 +146,8 @@ impl Transaction {
             signer,
             receiver_id,
             actions: Ok(Vec::new()),
+            wait_until: DEFAULT_WAIT_UNTIL,
+            balance_diff: false,
         }
     }
 
@@ -242,37 +255,77 @@ impl Transaction {
         self
     }
 
-    async fn transact_raw(self) -> Result<FinalExecutionOutcomeView> {
+    /// Specify up to which [`TxExecutionStatus`] to wait for when sending this transaction.
+    /// By default, this waits until the transaction and all of its receipts have been executed
+    /// optimistically (`ExecutedOptimistic`). Use `Executed` or `Final` to test logic that is
+    /// sensitive to finality.
+    ///
+    /// Levels which only guarantee inclusion into a block (`Included` and `IncludedFinal`) do
+    /// not provide an execution outcome, and can only be waited on through [`transact_async`].
+    ///
+    /// [`transact_async`]: Transaction::transact_async
+    pub fn wait_until(mut self, wait_until: TxExecutionStatus) -> Self {
+        self.wait_until = wait_until;
+        self
+    }
+
+    /// Record the balances of every account touched by this transaction before and after
+    /// its execution. The changes are reported by [`ExecutionFinalResult::balance_diff`].
+    /// Only supported by [`Transaction::transact`], as [`Transaction::transact_async`]
+    /// rejects it.
+    pub fn with_balance_diff(mut self) -> Self {
+        self.balance_diff = true;
+        self
+    }
+
+    async fn transact_raw(self) -> Result<FinalExecutionOutcomeWithReceiptView> {
         let view = send_batch_tx_and_retry(
             self.worker.client(),
             &self.signer,
             &self.receiver_id,
             self.actions?,
+            self.wait_until,
         )
         .await?;
 
-        if !self.worker.tx_callbacks.is_empty() {
-            let total_gas_burnt = view.transaction_outcome.outcome.gas_burnt
-                + view
-                    .receipts_outcome
-                    .iter()
-                    .map(|t| t.outcome.gas_burnt)
-                    .sum::<u64>();
-
-            for callback in self.worker.tx_callbacks {
-                callback(Gas::from_gas(total_gas_burnt))?;
-            }
-        }
-
         Ok(view)
     }
 
     /// Process the transaction, and return the result of the execution.
     pub async fn transact(self) -> Result<ExecutionFinalResult> {
-        self.transact_raw()
+        let worker = self.worker.clone();
+        let before = if self.balance_diff {
+            Some(latest_block_hash(&worker).await?)
+        } else {
+            None
+        };
+
+        let mut txn = self
+            .transact_raw()
             .await
             .map(ExecutionFinalResult::from_view)
-            .map_err(crate::error::Error::from)
+            .map_err(crate::error::Error::from)?;
+
+        if let Some(before) = before {
+            txn.balance_diff = Some(balance_diff(&worker, before, &txn).await?);
+        }
+
+        for callback in worker.tx_callbacks.iter() {
+            callback(&txn)?;
+        }
+        Ok(txn)
+    }
+
+    /// Estimate the gas and tokens this transaction would burn, without committing it.
+    ///
+    /// On sandbox, the transaction is executed in place, and every account it or its receipts
+    /// touched is reverted to its prior state afterwards. On any other network, nothing is
+    /// sent to the network: the accounts the transaction touches, including those reached by
+    /// cross-contract calls, are imported into a throwaway sandbox where it is executed
+    /// instead. The throwaway sandbox is spawned on the first estimation and reused by the
+    /// later ones of the same [`Worker`].
+    pub async fn estimate(self) -> Result<GasEstimate> {
+        estimate(&self.worker, &self.signer, &self.receiver_id, self.actions?).await
     }
 
     /// Send the transaction to the network to be processed. This will be done asynchronously
@@ -283,8 +336,17 @@ impl Transaction {
     ///
     /// [`status`]: TransactionStatus::status
     pub async fn transact_async(self) -> Result<TransactionStatus> {
-        send_batch_tx_async_and_retry(self.worker, &self.signer, &self.receiver_id, self.actions?)
-            .await
+        if self.balance_diff {
+            return Err(balance_diff_unsupported());
+        }
+        send_batch_tx_async_and_retry(
+            self.worker,
+            &self.signer,
+            &self.receiver_id,
+            self.actions?,
+            self.wait_until,
+        )
+        .await
     }
 }
 
@@ -295,6 +357,13 @@ pub struct CallTransaction {
     signer: InMemorySigner,
     contract_id: AccountId,
     function: Function,
+    /// Whether to inspect the allowance of the signing key before and after the call.
+    track_allowance: bool,
+    wait_until: TxExecutionStatus,
+    /// Whether to record the balances of touched accounts before and after the call.
+    balance_diff: bool,
+    /// ABI of the contract to check the call against before sending it.
+    abi: Option<Arc<ContractAbi>>,
 }
 
 impl CallTransaction {
@@ -309,6 +378,26 @@ impl CallTransaction {
             signer,
             contract_id,
             function: Function::new(function),
+            track_allowance: false,
+            wait_until: DEFAULT_WAIT_UNTIL,
+            balance_diff: false,
+            abi: None,
+        }
+    }
+
+    /// Check this call against `abi` before sending it. Set by [`Contract::call`] once an
+    /// ABI is attached to the contract.
+    ///
+    /// [`Contract::call`]: crate::Contract::call
+    pub(crate) fn abi(mut self, abi: Option<Arc<ContractAbi>>) -> Self {
+        self.abi = abi;
+        self
+    }
+
+    fn check_abi(&self) -> Result<()> {
+        match &self.abi {
+            Some(abi) => abi.check_call(&self.contract_id, &self.signer.account_id, &self.function),
+            None => Ok(()),
         }
     }
 
@@ -353,27 +442,86 @@ impl CallTransaction {
         self.gas(MAX_GAS)
     }
 
+    /// Sign this transaction with the provided [`SecretKey`] instead of the key of the
+    /// account that created this `CallTransaction`. The key must be an access key of the
+    /// signing account, such as a function call access key added via [`Transaction::add_key`].
+    ///
+    /// If the key is a function call access key with a limited allowance, the amount of
+    /// allowance consumed by this call is reported by [`ExecutionFinalResult::allowance_used`].
+    /// Calls that are not permitted by the key fail with [`ErrorKind::AccessKey`].
+    pub fn sign_with(mut self, sk: SecretKey) -> Self {
+        self.signer.secret_key = sk;
+        self.track_allowance()
+    }
+
+    /// Report the allowance consumed by this call, if it is signed by a function call
+    /// access key with a limited allowance.
+    pub(crate) fn track_allowance(mut self) -> Self {
+        self.track_allowance = true;
+        self
+    }
+
+    /// Specify up to which [`TxExecutionStatus`] to wait for when sending this transaction.
+    /// See [`Transaction::wait_until`] for more details.
+    pub fn wait_until(mut self, wait_until: TxExecutionStatus) -> Self {
+        self.wait_until = wait_until;
+        self
+    }
+
+    /// Record the balances of every account touched by this call before and after its
+    /// execution. The changes are reported by [`ExecutionFinalResult::balance_diff`].
+    /// Only supported by [`CallTransaction::transact`], as
+    /// [`CallTransaction::transact_async`] rejects it.
+    pub fn with_balance_diff(mut self) -> Self {
+        self.balance_diff = true;
+        self
+    }
+
     /// Finally, send the transaction to the network. This will consume the `CallTransaction`
     /// object and return us the execution details, along with any errors if the transaction
     /// failed in any process along the way.
     pub async fn transact(self) -> Result<ExecutionFinalResult> {
-        let txn = self
-            .worker
-            .client()
-            .call(
-                &self.signer,
-                &self.contract_id,
-                self.function.name.to_string(),
-                self.function.args?,
-                self.function.gas.as_gas(),
-                self.function.deposit,
-            )
-            .await
-            .map(ExecutionFinalResult::from_view)
-            .map_err(crate::error::Error::from)?;
+        self.check_abi()?;
+        let allowance_before = if self.track_allowance {
+            remaining_allowance(&self.worker, &self.signer).await?
+        } else {
+            None
+        };
+        let balances_before = if self.balance_diff {
+            Some(latest_block_hash(&self.worker).await?)
+        } else {
+            None
+        };
+
+        let mut txn = send_batch_tx_and_retry(
+            self.worker.client(),
+            &self.signer,
+            &self.contract_id,
+            vec![FunctionCallAction {
+                args: self.function.args?,
+                method_name: self.function.name,
+                gas: self.function.gas.as_gas(),
+                deposit: self.function.deposit.as_yoctonear(),
+            }
+            .into()],
+            self.wait_until,
+        )
+        .await
+        .map(ExecutionFinalResult::from_view)
+        .map_err(crate::error::Error::from)?;
+
+        if let Some(before) = allowance_before {
+            let after = remaining_allowance(&self.worker, &self.signer)
+                .await?
+                .unwrap_or(before);
+            txn.allowance_used = Some(before.saturating_sub(after));
+        }
+        if let Some(before) = balances_before {
+            txn.balance_diff = Some(balance_diff(&self.worker, before, &txn).await?);
+        }
 
         for callback in self.worker.tx_callbacks.iter() {
-            callback(txn.total_gas_burnt)?;
+            callback(&txn)?;
         }
         Ok(txn)
     }
@@ -386,6 +534,10 @@ impl CallTransaction {
     ///
     /// [`status`]: TransactionStatus::status
     pub async fn transact_async(self) -> Result<TransactionStatus> {
+        if self.balance_diff {
+            return Err(balance_diff_unsupported());
+        }
+        self.check_abi()?;
         send_batch_tx_async_and_retry(
             self.worker,
             &self.signer,
@@ -397,6 +549,26 @@ impl CallTransaction {
                 deposit: self.function.deposit.as_yoctonear(),
             }
             .into()],
+            self.wait_until,
+        )
+        .await
+    }
+
+    /// Estimate the gas and tokens this call would burn, without committing it.
+    /// See [`Transaction::estimate`] for how the estimation is done.
+    pub async fn estimate(self) -> Result<GasEstimate> {
+        self.check_abi()?;
+        estimate(
+            &self.worker,
+            &self.signer,
+            &self.contract_id,
+            vec![FunctionCallAction {
+                args: self.function.args?,
+                method_name: self.function.name,
+                gas: self.function.gas.as_gas(),
+                deposit: self.function.deposit.as_yoctonear(),
+            }
+            .into()],
         )
         .await
     }
@@ -408,12 +580,157 @@ impl CallTransaction {
             ViewFunction {
                 account_id: self.contract_id.clone(),
                 function: self.function,
+                abi: self.abi,
             },
         )
         .await
     }
 }
 
+/// Grab the remaining allowance of the signer's key, if it is a function call access
+/// key with a limited allowance.
+async fn remaining_allowance(
+    worker: &Worker<dyn Network>,
+    signer: &InMemorySigner,
+) -> Result<Option<NearToken>> {
+    let access_key = Query::new(
+        worker.client(),
+        ViewAccessKey {
+            account_id: signer.account_id.clone(),
+            public_key: signer.secret_key.public_key(),
+        },
+    )
+    .await?;
+
+    Ok(match access_key.permission {
+        AccessKeyPermission::FunctionCall(permission) => permission.allowance,
+        AccessKeyPermission::FullAccess => None,
+    })
+}
+
+/// Grab the hash of the latest block, which allows viewing the balances of accounts as
+/// they were before a transaction got sent.
+async fn latest_block_hash(worker: &Worker<dyn Network>) -> Result<CryptoHash> {
+    Ok(*worker.view_block().await?.hash())
+}
+
+/// Error for asking [`Transaction::with_balance_diff`] of a transaction sent asynchronously,
+/// whose balances cannot be recorded before it gets executed.
+fn balance_diff_unsupported() -> Error {
+    ErrorKind::Usage.message(
+        "`with_balance_diff` is not supported by `transact_async`, as balances cannot be \
+         recorded around a transaction that is not waited for; use `transact` instead",
+    )
+}
+
+/// Compare the balances of the signer and every account a receipt got executed on, as they
+/// were at the block `before` and as they are now. Accounts that do not exist at either
+/// point are reported as such, while failing to view them for any other reason fails.
+async fn balance_diff(
+    worker: &Worker<dyn Network>,
+    before: CryptoHash,
+    txn: &ExecutionFinalResult,
+) -> Result<BalanceDiff> {
+    let signer_id = &txn.outcome().executor_id;
+    let mut account_ids = vec![signer_id];
+    for receipt in txn.receipt_outcomes() {
+        if !account_ids.contains(&&receipt.executor_id) {
+            account_ids.push(&receipt.executor_id);
+        }
+    }
+
+    let mut accounts = Vec::with_capacity(account_ids.len());
+    for account_id in account_ids {
+        let balance_before = worker.view_account(account_id).block_hash(before).await;
+        let balance_before = unless_unknown_account(balance_before)?.map(AccountBalance::from);
+        let balance_after = unless_unknown_account(worker.view_account(account_id).await)?
+            .map(AccountBalance::from);
+
+        // The signer prepays for all the gas and gets refunded for what was not burnt.
+        let gas_cost = if account_id == signer_id {
+            txn.outcomes()
+                .iter()
+                .map(|outcome| outcome.tokens_burnt)
+                .fold(NearToken::from_yoctonear(0), NearToken::saturating_add)
+        } else {
+            NearToken::from_yoctonear(0)
+        };
+
+        accounts.push(AccountBalanceDiff::new(
+            account_id.clone(),
+            balance_before,
+            balance_after,
+            gas_cost,
+        ));
+    }
+
+    Ok(BalanceDiff::new(accounts))
+}
+
+/// Turn the failure of viewing an account that does not exist into `None`.
+fn unless_unknown_account<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if err.is_unknown_account() => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Upgrades a contract to new code, optionally migrating its state, through a single batch
+/// transaction. Created through [`Contract::upgrade`].
+///
+/// [`Contract::upgrade`]: crate::Contract::upgrade
+pub struct UpgradeTransaction {
+    contract: Contract,
+    wasm: Vec<u8>,
+    migrate: Option<Function>,
+}
+
+impl UpgradeTransaction {
+    pub(crate) fn new(contract: Contract, wasm: &[u8]) -> Self {
+        Self {
+            contract,
+            wasm: wasm.to_vec(),
+            migrate: None,
+        }
+    }
+
+    /// Call the `function` migration method of the new code with JSON `args`, right after
+    /// deploying it. The call gets the maximum amount of gas.
+    pub fn migrate<U: serde::Serialize>(self, function: &str, args: U) -> Self {
+        self.migrate_with(Function::new(function).args_json(args).max_gas())
+    }
+
+    /// Call the given migration method of the new code right after deploying it. Allows
+    /// providing borsh args, gas or a deposit, unlike [`UpgradeTransaction::migrate`].
+    pub fn migrate_with(mut self, function: Function) -> Self {
+        self.migrate = Some(function);
+        self
+    }
+
+    /// Deploy the new code and call the migration method, if any, in one batch transaction.
+    /// The raw state of the contract is captured before and after, and compared in the
+    /// returned [`UpgradeResult`]. Use [`UpgradeResult::failed_step`] or
+    /// [`UpgradeResult::into_result`] to find out whether the deploy or the migration failed.
+    pub async fn transact(self) -> Result<UpgradeResult> {
+        let state_before: ContractState = self.contract.view_state().await?.into();
+
+        let mut batch = self.contract.batch().deploy(&self.wasm);
+        if let Some(function) = self.migrate {
+            batch = batch.call(function);
+        }
+        let details = batch.transact().await?;
+
+        let state_after: ContractState = self.contract.view_state().await?.into();
+        Ok(UpgradeResult {
+            state_diff: StateDiff::new(&state_before, &state_after),
+            details,
+            state_before,
+            state_after,
+        })
+    }
+}
+
 /// Similar to a [`Transaction`], but more specific to creating an account.
 /// This transaction will create a new account with the specified `receiver_id`
 pub struct CreateAccountTransaction<'a, 'b> {
@@ -424,6 +741,7 @@ pub struct CreateAccountTransaction<'a, 'b> {
 
     initial_balance: NearToken,
     secret_key: Option<SecretKey>,
+    wait_until: TxExecutionStatus,
 }
 
 impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
@@ -440,6 +758,7 @@ impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
             new_account_id,
             initial_balance: NearToken::from_yoctonear(100000000000000000000000u128),
             secret_key: None,
+            wait_until: DEFAULT_WAIT_UNTIL,
         }
     }
 
@@ -456,6 +775,13 @@ impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
         self
     }
 
+    /// Specify up to which [`TxExecutionStatus`] to wait for when sending this transaction.
+    /// See [`Transaction::wait_until`] for more details.
+    pub fn wait_until(mut self, wait_until: TxExecutionStatus) -> Self {
+        self.wait_until = wait_until;
+        self
+    }
+
     /// Send the transaction to the network. This will consume the `CreateAccountTransaction`
     /// and give us back the details of the execution and finally the new [`Account`] object.
     pub async fn transact(self) -> Result<Execution<Account>> {
@@ -469,7 +795,13 @@ impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
         let outcome = self
             .worker
             .client()
-            .create_account(&self.signer, &id, sk.public_key(), self.initial_balance)
+            .create_account(
+                &self.signer,
+                &id,
+                sk.public_key(),
+                self.initial_balance,
+                self.wait_until,
+            )
             .await?;
 
         let signer = InMemorySigner::from_secret_key(id, sk);
@@ -477,7 +809,10 @@ impl<'a, 'b> CreateAccountTransaction<'a, 'b> {
         let details = ExecutionFinalResult::from_view(outcome);
 
         for callback in self.worker.tx_callbacks.iter() {
-            callback(details.total_gas_burnt)?;
+            callback(&details)?;
+        }
+        if details.is_success() {
+            self.worker.track_account(account.signer())?;
         }
 
         Ok(Execution {
@@ -496,6 +831,7 @@ pub struct TransactionStatus {
     worker: Worker<dyn Network>,
     sender_id: AccountId,
     hash: CryptoHash,
+    wait_until: TxExecutionStatus,
 }
 
 impl TransactionStatus {
@@ -503,17 +839,20 @@ impl TransactionStatus {
         worker: Worker<dyn Network>,
         id: AccountId,
         hash: near_primitives::hash::CryptoHash,
+        wait_until: TxExecutionStatus,
     ) -> Self {
         Self {
             worker,
             sender_id: id,
             hash: CryptoHash(hash.0),
+            wait_until,
         }
     }
 
     /// Checks the status of the transaction. If an `Err` is returned, then the transaction
     /// is in an unexpected state. The error should have further context. Otherwise, if an
-    /// `Ok` value with [`Poll::Pending`] is returned, then the transaction has not finished.
+    /// `Ok` value with [`Poll::Pending`] is returned, then the transaction has not finished
+    /// or has not yet reached the execution level specified through `wait_until`.
     pub async fn status(&self) -> Result<Poll<ExecutionFinalResult>> {
         let rpc_resp = self
             .worker
@@ -535,7 +874,9 @@ impl TransactionStatus {
             },
         };
 
-        if matches!(rpc_resp.final_execution_status, TxExecutionStatus::Included) {
+        if matches!(rpc_resp.final_execution_status, TxExecutionStatus::Included)
+            || execution_level(&rpc_resp.final_execution_status) < execution_level(&self.wait_until)
+        {
             return Ok(Poll::Pending);
         }
 
@@ -544,9 +885,17 @@ impl TransactionStatus {
             return Ok(Poll::Pending);
         };
 
-        let outcome = final_outcome.into_outcome();
+        let outcome = match final_outcome {
+            FinalExecutionOutcomeViewEnum::FinalExecutionOutcomeWithReceipt(outcome) => outcome,
+            FinalExecutionOutcomeViewEnum::FinalExecutionOutcome(final_outcome) => {
+                FinalExecutionOutcomeWithReceiptView {
+                    final_outcome,
+                    receipts: Vec::new(),
+                }
+            }
+        };
 
-        match outcome.status {
+        match outcome.final_outcome.status {
             near_primitives::views::FinalExecutionStatus::NotStarted => return Ok(Poll::Pending),
             near_primitives::views::FinalExecutionStatus::Started => return Ok(Poll::Pending),
             _ => (),
@@ -1,3 +1,4 @@
 //! All traits that are essential to the ease of use of workspaces.
 
 pub use crate::network::TopLevelAccountCreator;
+pub use crate::types::ContractStateExt;
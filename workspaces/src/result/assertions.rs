@@ -0,0 +1,210 @@
+//! Fluent assertions on the results of executions, for use in tests.
+//!
+//! Every assertion returns the result back, so multiple assertions can be chained:
+//!
+//! ```ignore
+//! contract
+//!     .call("set_status")
+//!     .args_json(json!({ "message": "hello" }))
+//!     .transact()
+//!     .await?
+//!     .assert_success()
+//!     .assert_log("set_status with message hello")
+//!     .assert_gas_below(Gas::from_tgas(10));
+//! ```
+//!
+//! When an assertion fails, the panic message includes the receipt tree of the execution
+//! along with all of its logs.
+
+use std::fmt::Write as _;
+
+use near_primitives::views::FinalExecutionStatus;
+
+use super::{
+    ExecutionDetails, ExecutionFailure, ExecutionFinalResult, ExecutionResult, ExecutionSuccess,
+};
+use crate::types::Gas;
+
+impl ExecutionDetails {
+    /// Panic with the given message, followed by the details of this execution.
+    #[track_caller]
+    fn fail(&self, msg: &str) -> ! {
+        let mut context = format!("{msg}\n\nreceipts:\n{}", self.receipt_tree());
+        context.push_str("\nlogs:\n");
+        for outcome in self.outcomes() {
+            for log in &outcome.logs {
+                let _ = writeln!(context, "  [{}] {log}", outcome.executor_id);
+            }
+        }
+        panic!("{}", context);
+    }
+
+    #[track_caller]
+    fn assert_log(&self, pattern: &str) {
+        if !self.logs().iter().any(|log| log.contains(pattern)) {
+            self.fail(&format!("expected a log containing `{pattern}`"));
+        }
+    }
+
+    #[track_caller]
+    fn assert_event(&self, standard: &str, event: &str) {
+        let found = self
+            .events()
+            .iter()
+            .any(|e| e.standard == standard && e.event == event);
+        if !found {
+            self.fail(&format!(
+                "expected a `{event}` event of standard `{standard}`"
+            ));
+        }
+    }
+
+    #[track_caller]
+    fn assert_receipt_count(&self, count: usize) {
+        if self.receipts.len() != count {
+            self.fail(&format!(
+                "expected {count} receipts, but found {}",
+                self.receipts.len()
+            ));
+        }
+    }
+
+    #[track_caller]
+    fn assert_gas_below(&self, total_gas_burnt: Gas, limit: Gas) {
+        if total_gas_burnt >= limit {
+            self.fail(&format!(
+                "expected less than {limit} to be burnt, but {total_gas_burnt} was burnt"
+            ));
+        }
+    }
+}
+
+impl ExecutionFinalResult {
+    /// Assert that the transaction succeeded.
+    #[track_caller]
+    pub fn assert_success(&self) -> &Self {
+        if let FinalExecutionStatus::Failure(err) = &self.status {
+            self.details.fail(&format!(
+                "expected the transaction to succeed, but it failed: {err}"
+            ));
+        }
+        self
+    }
+
+    /// Assert that the transaction failed with an error whose message contains `msg`.
+    #[track_caller]
+    pub fn assert_failure_contains(&self, msg: &str) -> &Self {
+        match &self.status {
+            FinalExecutionStatus::Failure(err) if err.to_string().contains(msg) => {}
+            FinalExecutionStatus::Failure(err) => self.details.fail(&format!(
+                "expected the transaction to fail with `{msg}`, but it failed with: {err}"
+            )),
+            _ => self.details.fail(&format!(
+                "expected the transaction to fail with `{msg}`, but it succeeded"
+            )),
+        }
+        self
+    }
+
+    /// Assert that the transaction or one of its receipts emitted a log containing `pattern`.
+    #[track_caller]
+    pub fn assert_log(&self, pattern: &str) -> &Self {
+        self.details.assert_log(pattern);
+        self
+    }
+
+    /// Assert that the transaction or one of its receipts emitted a NEP-297 event of type
+    /// `event` from the standard `standard`.
+    #[track_caller]
+    pub fn assert_event(&self, standard: &str, event: &str) -> &Self {
+        self.details.assert_event(standard, event);
+        self
+    }
+
+    /// Assert that the transaction generated exactly `count` receipts.
+    #[track_caller]
+    pub fn assert_receipt_count(&self, count: usize) -> &Self {
+        self.details.assert_receipt_count(count);
+        self
+    }
+
+    /// Assert that the transaction and all of its receipts burnt less than `limit` gas.
+    #[track_caller]
+    pub fn assert_gas_below(&self, limit: Gas) -> &Self {
+        self.details.assert_gas_below(self.total_gas_burnt, limit);
+        self
+    }
+}
+
+impl ExecutionSuccess {
+    /// Assert that the transaction succeeded, which is always the case for an
+    /// [`ExecutionSuccess`]. Provided so assertions read the same on every result type.
+    #[track_caller]
+    pub fn assert_success(&self) -> &Self {
+        self
+    }
+
+    /// Assert that the transaction failed, which is never the case for an
+    /// [`ExecutionSuccess`], so this always panics.
+    #[track_caller]
+    pub fn assert_failure_contains(&self, msg: &str) -> &Self {
+        self.details.fail(&format!(
+            "expected the transaction to fail with `{msg}`, but it succeeded"
+        ))
+    }
+}
+
+impl ExecutionFailure {
+    /// Assert that the transaction succeeded, which is never the case for an
+    /// [`ExecutionFailure`], so this always panics.
+    #[track_caller]
+    pub fn assert_success(&self) -> &Self {
+        self.details.fail(&format!(
+            "expected the transaction to succeed, but it failed: {}",
+            self.value
+        ))
+    }
+
+    /// Assert that the transaction failed with an error whose message contains `msg`.
+    #[track_caller]
+    pub fn assert_failure_contains(&self, msg: &str) -> &Self {
+        if !self.value.to_string().contains(msg) {
+            self.details.fail(&format!(
+                "expected the transaction to fail with `{msg}`, but it failed with: {}",
+                self.value
+            ));
+        }
+        self
+    }
+}
+
+impl<T> ExecutionResult<T> {
+    /// Assert that the transaction or one of its receipts emitted a log containing `pattern`.
+    #[track_caller]
+    pub fn assert_log(&self, pattern: &str) -> &Self {
+        self.details.assert_log(pattern);
+        self
+    }
+
+    /// Assert that the transaction or one of its receipts emitted a NEP-297 event of type
+    /// `event` from the standard `standard`.
+    #[track_caller]
+    pub fn assert_event(&self, standard: &str, event: &str) -> &Self {
+        self.details.assert_event(standard, event);
+        self
+    }
+
+    /// Assert that the transaction generated exactly `count` receipts.
+    #[track_caller]
+    pub fn assert_receipt_count(&self, count: usize) -> &Self {
+        self.details.assert_receipt_count(count);
+        self
+    }
+
+    /// Assert that the transaction and all of its receipts burnt less than `limit` gas.
+    #[track_caller]
+    pub fn assert_gas_below(&self, limit: Gas) -> &Self {
+        self.details.assert_gas_below(self.total_gas_burnt, limit);
+        self
+    }
+}
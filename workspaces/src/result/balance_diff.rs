@@ -0,0 +1,169 @@
+//! Changes in the balances of the accounts touched by a transaction.
+
+use std::fmt;
+
+use near_account_id::AccountId;
+use near_primitives::types::StorageUsage;
+
+use crate::types::account::AccountDetails;
+use crate::types::NearToken;
+
+/// Changes in the balances and storage of every account touched by a transaction,
+/// recorded when a transaction is sent with `with_balance_diff()`. Grab it from
+/// [`ExecutionFinalResult::balance_diff`].
+///
+/// [`ExecutionFinalResult::balance_diff`]: crate::result::ExecutionFinalResult::balance_diff
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceDiff {
+    accounts: Vec<AccountBalanceDiff>,
+}
+
+/// Balance and storage of an account at a specific point in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AccountBalance {
+    /// The liquid balance of the account.
+    pub amount: NearToken,
+    /// The balance locked for staking.
+    pub locked: NearToken,
+    /// The amount of storage used by the account, in bytes.
+    pub storage_usage: StorageUsage,
+}
+
+/// Changes in the balance and storage of a single account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AccountBalanceDiff {
+    pub account_id: AccountId,
+    /// Balance before the transaction. `None` if the account did not exist yet.
+    pub before: Option<AccountBalance>,
+    /// Balance after the transaction. `None` if the account was deleted.
+    pub after: Option<AccountBalance>,
+    /// Tokens this account paid for the gas burnt by the transaction and its receipts.
+    /// Only the signer pays for gas.
+    pub gas_cost: NearToken,
+}
+
+impl From<AccountDetails> for AccountBalance {
+    fn from(details: AccountDetails) -> Self {
+        Self {
+            amount: details.balance,
+            locked: details.locked,
+            storage_usage: details.storage_usage,
+        }
+    }
+}
+
+impl AccountBalanceDiff {
+    pub(crate) fn new(
+        account_id: AccountId,
+        before: Option<AccountBalance>,
+        after: Option<AccountBalance>,
+        gas_cost: NearToken,
+    ) -> Self {
+        Self {
+            account_id,
+            before,
+            after,
+            gas_cost,
+        }
+    }
+
+    fn delta(&self, f: impl Fn(&AccountBalance) -> i128) -> i128 {
+        self.after.as_ref().map_or(0, &f) - self.before.as_ref().map_or(0, &f)
+    }
+
+    /// Change in the liquid balance of the account, in yoctoNEAR.
+    pub fn amount_delta(&self) -> i128 {
+        self.delta(|balance| balance.amount.as_yoctonear() as i128)
+    }
+
+    /// Change in the balance locked for staking, in yoctoNEAR.
+    pub fn locked_delta(&self) -> i128 {
+        self.delta(|balance| balance.locked.as_yoctonear() as i128)
+    }
+
+    /// Change in the storage used by the account, in bytes.
+    pub fn storage_usage_delta(&self) -> i64 {
+        self.delta(|balance| balance.storage_usage as i128) as i64
+    }
+
+    /// Change in the liquid balance of the account that is not due to paying for gas,
+    /// in yoctoNEAR. This covers deposits, transfers and refunds. Gas rewards paid out to
+    /// contracts are included here as well.
+    pub fn transfer_delta(&self) -> i128 {
+        self.amount_delta() + self.gas_cost.as_yoctonear() as i128
+    }
+}
+
+impl BalanceDiff {
+    pub(crate) fn new(accounts: Vec<AccountBalanceDiff>) -> Self {
+        Self { accounts }
+    }
+
+    /// Changes of every account touched by the transaction, starting with the signer.
+    pub fn accounts(&self) -> &[AccountBalanceDiff] {
+        &self.accounts
+    }
+
+    /// Changes of a specific account, if it was touched by the transaction.
+    pub fn get(&self, account_id: &AccountId) -> Option<&AccountBalanceDiff> {
+        self.accounts
+            .iter()
+            .find(|diff| &diff.account_id == account_id)
+    }
+
+    /// Total tokens paid for gas by the transaction.
+    pub fn gas_cost(&self) -> NearToken {
+        NearToken::from_yoctonear(
+            self.accounts
+                .iter()
+                .map(|diff| diff.gas_cost.as_yoctonear())
+                .sum(),
+        )
+    }
+
+    /// Net change in the liquid balance of all touched accounts together, in yoctoNEAR.
+    pub fn net_amount_delta(&self) -> i128 {
+        self.accounts
+            .iter()
+            .map(AccountBalanceDiff::amount_delta)
+            .sum()
+    }
+
+    /// Net change in the storage used by all touched accounts together, in bytes.
+    pub fn net_storage_usage_delta(&self) -> i64 {
+        self.accounts
+            .iter()
+            .map(AccountBalanceDiff::storage_usage_delta)
+            .sum()
+    }
+}
+
+impl fmt::Display for BalanceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<40} {:>40} {:>40} {:>40} {:>12}",
+            "account", "amount (yocto)", "transfers (yocto)", "locked (yocto)", "storage (B)"
+        )?;
+        for diff in &self.accounts {
+            writeln!(
+                f,
+                "{:<40} {:>+40} {:>+40} {:>+40} {:>+12}",
+                diff.account_id.as_str(),
+                diff.amount_delta(),
+                diff.transfer_delta(),
+                diff.locked_delta(),
+                diff.storage_usage_delta()
+            )?;
+        }
+        write!(
+            f,
+            "net: {:+} yoctoNEAR, {:+} bytes of storage, {} paid for gas",
+            self.net_amount_delta(),
+            self.net_storage_usage_delta(),
+            self.gas_cost()
+        )
+    }
+}
@@ -0,0 +1,267 @@
+//! Parsing of [NEP-297](https://nomicon.io/Standards/EventsFormat) events emitted
+//! through the logs of an execution.
+
+use near_account_id::AccountId;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{ExecutionDetails, ExecutionOutcome, Result};
+use crate::error::ErrorKind;
+
+/// Prefix of logs that contain a NEP-297 event.
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// A NEP-297 event emitted by a contract through its logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Event {
+    /// Name of the standard, e.g. `nep141`.
+    pub standard: String,
+    /// Version of the standard, e.g. `1.0.0`.
+    pub version: String,
+    /// Type of the event, e.g. `ft_transfer`.
+    pub event: String,
+    /// Associated event data, if any.
+    pub data: Option<serde_json::Value>,
+    /// The account of the contract that emitted this event.
+    pub executor_id: AccountId,
+}
+
+/// The JSON representation of an event as it is logged by contracts.
+#[derive(Deserialize)]
+struct EventLog {
+    standard: String,
+    version: String,
+    event: String,
+    data: Option<serde_json::Value>,
+}
+
+impl Event {
+    /// Parse an event out of a log. Returns `None` if the log is not a well formed event.
+    pub(crate) fn from_log(log: &str, executor_id: &AccountId) -> Option<Self> {
+        let json = log.strip_prefix(EVENT_JSON_PREFIX)?;
+        let EventLog {
+            standard,
+            version,
+            event,
+            data,
+        } = serde_json::from_str(json.trim()).ok()?;
+
+        Some(Self {
+            standard,
+            version,
+            event,
+            data,
+            executor_id: executor_id.clone(),
+        })
+    }
+
+    /// Deserialize the data of this event into an instance of type `T`.
+    pub fn data<T: DeserializeOwned>(&self) -> Result<T> {
+        let data = self.data.clone().unwrap_or(serde_json::Value::Null);
+        serde_json::from_value(data).map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+
+    /// Deserialize this event into one of the events of the [`StandardEvent`] `E`.
+    pub fn typed<E: StandardEvent>(&self) -> Result<E> {
+        self.deserialize_typed()
+            .map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+
+    fn deserialize_typed<E: StandardEvent>(&self) -> serde_json::Result<E> {
+        let event = serde_json::json!({
+            "event": self.event,
+            "data": self.data,
+        });
+        serde_json::from_value(event)
+    }
+}
+
+/// Set of events defined by a standard, which can be grabbed from an execution through
+/// `typed_events`. Implementors are deserialized from an object with the event type in its
+/// `event` field and the event data in its `data` field.
+pub trait StandardEvent: DeserializeOwned {
+    /// Name of the standard these events belong to, e.g. `nep141`.
+    const STANDARD: &'static str;
+}
+
+/// Events of the [NEP-141](https://nomicon.io/Standards/Tokens/FungibleToken/Event)
+/// fungible token standard.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Nep141Event {
+    FtMint(Vec<FtMint>),
+    FtTransfer(Vec<FtTransfer>),
+    FtBurn(Vec<FtBurn>),
+}
+
+impl StandardEvent for Nep141Event {
+    const STANDARD: &'static str = "nep141";
+}
+
+/// Data of a NEP-141 `ft_mint` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtMint {
+    pub owner_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub amount: u128,
+    pub memo: Option<String>,
+}
+
+/// Data of a NEP-141 `ft_transfer` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtTransfer {
+    pub old_owner_id: AccountId,
+    pub new_owner_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub amount: u128,
+    pub memo: Option<String>,
+}
+
+/// Data of a NEP-141 `ft_burn` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtBurn {
+    pub owner_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub amount: u128,
+    pub memo: Option<String>,
+}
+
+/// Events of the [NEP-171](https://nomicon.io/Standards/Tokens/NonFungibleToken/Event)
+/// non-fungible token standard.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Nep171Event {
+    NftMint(Vec<NftMint>),
+    NftTransfer(Vec<NftTransfer>),
+    NftBurn(Vec<NftBurn>),
+    ContractMetadataUpdate(Vec<ContractMetadataUpdate>),
+}
+
+impl StandardEvent for Nep171Event {
+    const STANDARD: &'static str = "nep171";
+}
+
+/// Data of a NEP-171 `nft_mint` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftMint {
+    pub owner_id: AccountId,
+    pub token_ids: Vec<String>,
+    pub memo: Option<String>,
+}
+
+/// Data of a NEP-171 `nft_transfer` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftTransfer {
+    pub authorized_id: Option<AccountId>,
+    pub old_owner_id: AccountId,
+    pub new_owner_id: AccountId,
+    pub token_ids: Vec<String>,
+    pub memo: Option<String>,
+}
+
+/// Data of a NEP-171 `nft_burn` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftBurn {
+    pub authorized_id: Option<AccountId>,
+    pub owner_id: AccountId,
+    pub token_ids: Vec<String>,
+    pub memo: Option<String>,
+}
+
+/// Data of a NEP-171 `contract_metadata_update` event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractMetadataUpdate {
+    pub memo: Option<String>,
+}
+
+/// (De)serialize a `u128` as a decimal string, which is how token standards represent
+/// amounts in JSON.
+pub(crate) mod dec_format {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(num: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&num.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl ExecutionOutcome {
+    /// Grab all the well formed NEP-297 events emitted through the logs of this outcome.
+    pub fn events(&self) -> Vec<Event> {
+        self.logs
+            .iter()
+            .filter_map(|log| Event::from_log(log, &self.executor_id))
+            .collect()
+    }
+
+    /// Grab the data of all the events of type `event` from the standard `standard`
+    /// emitted by this outcome, deserialized into instances of type `T`.
+    pub fn events_of<T: DeserializeOwned>(&self, standard: &str, event: &str) -> Result<Vec<T>> {
+        events_of(self.events(), standard, event)
+    }
+
+    /// Grab all the events of the standard `E` emitted by this outcome, such as
+    /// [`Nep141Event`] or [`Nep171Event`]. Events of a type `E` has no variant for, such as
+    /// those added by later versions of the standard, are skipped.
+    pub fn typed_events<E: StandardEvent>(&self) -> Result<Vec<E>> {
+        typed_events(self.events())
+    }
+}
+
+impl ExecutionDetails {
+    /// Grab all the events emitted by both the transaction and receipt outcomes.
+    pub fn events(&self) -> Vec<Event> {
+        self.outcomes()
+            .into_iter()
+            .flat_map(ExecutionOutcome::events)
+            .collect()
+    }
+
+    /// Grab the data of all the events of type `event` from the standard `standard`
+    /// emitted by both the transaction and receipt outcomes, deserialized into instances of
+    /// type `T`.
+    pub fn events_of<T: DeserializeOwned>(&self, standard: &str, event: &str) -> Result<Vec<T>> {
+        events_of(self.events(), standard, event)
+    }
+
+    /// Grab all the events of the standard `E` emitted by both the transaction and receipt
+    /// outcomes. Events of a type `E` has no variant for are skipped.
+    pub fn typed_events<E: StandardEvent>(&self) -> Result<Vec<E>> {
+        typed_events(self.events())
+    }
+}
+
+/// Deserialize the data of the events of type `event` from the standard `standard`.
+fn events_of<T: DeserializeOwned>(
+    events: Vec<Event>,
+    standard: &str,
+    event: &str,
+) -> Result<Vec<T>> {
+    events
+        .iter()
+        .filter(|e| e.standard == standard && e.event == event)
+        .map(Event::data)
+        .collect()
+}
+
+/// Deserialize the events of the standard `E`. The standard events are non exhaustive, so an
+/// event of a type `E` has no variant for is skipped rather than failing all the others.
+fn typed_events<E: StandardEvent>(events: Vec<Event>) -> Result<Vec<E>> {
+    let mut typed = Vec::new();
+    for event in events.iter().filter(|e| e.standard == E::STANDARD) {
+        match event.deserialize_typed() {
+            Ok(event) => typed.push(event),
+            Err(err) if err.to_string().starts_with("unknown variant") => {}
+            Err(err) => return Err(ErrorKind::DataConversion.custom(err)),
+        }
+    }
+    Ok(typed)
+}
@@ -0,0 +1,233 @@
+//! Structured access to the errors of failed executions.
+
+use std::fmt;
+
+use near_account_id::AccountId;
+use near_primitives::errors::{
+    ActionErrorKind, CompilationError, FunctionCallError, HostError, InvalidTxError,
+    MethodResolveError, TxExecutionError,
+};
+use near_primitives::views::ExecutionStatusView;
+
+use super::{ExecutionFailure, ExecutionOutcome};
+use crate::types::NearToken;
+
+/// Prefix the runtime prepends to the message of a contract panic.
+const PANIC_PREFIX: &str = "Smart contract panicked: ";
+
+/// Messages the runtime reports when an execution runs out of gas.
+const OUT_OF_GAS_MESSAGES: [&str; 2] = [
+    "Exceeded the prepaid gas.",
+    "Exceeded the maximum amount of gas allowed to burn per contract.",
+];
+
+/// Common reasons for a transaction or receipt to fail, mapped from the errors
+/// found in [`near_primitives::errors`]. This allows asserting on the behavior of a
+/// failure instead of matching on its message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExecutionFailureKind {
+    /// The contract panicked with the given message.
+    ContractPanic(String),
+    /// The contract panicked because the attached deposit was not enough, such as when
+    /// a method requires exactly 1 yoctoNEAR or a storage deposit. Contains the panic message.
+    InsufficientDeposit(String),
+    /// The called method is not exported by the contract.
+    MethodNotFound,
+    /// The account being called into does not have a contract deployed.
+    ContractNotDeployed(AccountId),
+    /// The execution ran out of the gas attached to it.
+    OutOfGas,
+    /// The account the action was performed on does not exist.
+    AccountDoesNotExist(AccountId),
+    /// The account to be created already exists.
+    AccountAlreadyExists(AccountId),
+    /// The account would not have enough balance left to cover its storage.
+    LackBalanceForState {
+        account_id: AccountId,
+        amount: NearToken,
+    },
+    /// The signer does not have enough balance to cover the cost of the transaction.
+    NotEnoughBalance {
+        signer_id: AccountId,
+        balance: NearToken,
+        cost: NearToken,
+    },
+    /// Any other failure of a function call, such as a contract that failed to compile
+    /// or a host function that failed.
+    FunctionCall(FunctionCallError),
+    /// Any other failure, with the message of the underlying error.
+    Other(String),
+}
+
+impl fmt::Display for ExecutionFailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContractPanic(msg) => write!(f, "contract panicked: {msg}"),
+            Self::InsufficientDeposit(msg) => write!(f, "insufficient deposit: {msg}"),
+            Self::MethodNotFound => write!(f, "method not found"),
+            Self::ContractNotDeployed(id) => write!(f, "no contract deployed on {id}"),
+            Self::OutOfGas => write!(f, "out of gas"),
+            Self::AccountDoesNotExist(id) => write!(f, "account {id} does not exist"),
+            Self::AccountAlreadyExists(id) => write!(f, "account {id} already exists"),
+            Self::LackBalanceForState { account_id, amount } => write!(
+                f,
+                "account {account_id} needs {amount} more to cover its storage"
+            ),
+            Self::NotEnoughBalance {
+                signer_id,
+                balance,
+                cost,
+            } => write!(
+                f,
+                "signer {signer_id} has a balance of {balance}, which does not cover {cost}"
+            ),
+            // The runtime does not implement `Display` for these errors either.
+            Self::FunctionCall(err) => write!(f, "{err:?}"),
+            Self::Other(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl From<&TxExecutionError> for ExecutionFailureKind {
+    fn from(err: &TxExecutionError) -> Self {
+        match err {
+            TxExecutionError::ActionError(err) => match &err.kind {
+                ActionErrorKind::FunctionCallError(err) => function_call_failure(err),
+                ActionErrorKind::AccountDoesNotExist { account_id } => {
+                    Self::AccountDoesNotExist(account_id.clone())
+                }
+                ActionErrorKind::AccountAlreadyExists { account_id } => {
+                    Self::AccountAlreadyExists(account_id.clone())
+                }
+                ActionErrorKind::LackBalanceForState { account_id, amount } => {
+                    Self::LackBalanceForState {
+                        account_id: account_id.clone(),
+                        amount: NearToken::from_yoctonear(*amount),
+                    }
+                }
+                kind => Self::Other(kind.to_string()),
+            },
+            TxExecutionError::InvalidTxError(InvalidTxError::NotEnoughBalance {
+                signer_id,
+                balance,
+                cost,
+            }) => Self::NotEnoughBalance {
+                signer_id: signer_id.clone(),
+                balance: NearToken::from_yoctonear(*balance),
+                cost: NearToken::from_yoctonear(*cost),
+            },
+            TxExecutionError::InvalidTxError(err) => Self::Other(err.to_string()),
+        }
+    }
+}
+
+fn function_call_failure(err: &FunctionCallError) -> ExecutionFailureKind {
+    if let Some(msg) = function_call_panic(err) {
+        // Deposit checks are done by the contract itself, so the best we can do is to
+        // recognize the messages used by near-sdk and the token standards.
+        return if msg.to_lowercase().contains("attached deposit") {
+            ExecutionFailureKind::InsufficientDeposit(msg.to_string())
+        } else {
+            ExecutionFailureKind::ContractPanic(msg.to_string())
+        };
+    }
+
+    match err {
+        FunctionCallError::MethodResolveError(MethodResolveError::MethodNotFound) => {
+            ExecutionFailureKind::MethodNotFound
+        }
+        FunctionCallError::CompilationError(CompilationError::CodeDoesNotExist { account_id }) => {
+            ExecutionFailureKind::ContractNotDeployed(account_id.clone())
+        }
+        FunctionCallError::HostError(HostError::GasExceeded | HostError::GasLimitExceeded) => {
+            ExecutionFailureKind::OutOfGas
+        }
+        FunctionCallError::ExecutionError(msg) if OUT_OF_GAS_MESSAGES.contains(&msg.as_str()) => {
+            ExecutionFailureKind::OutOfGas
+        }
+        FunctionCallError::ExecutionError(msg) => ExecutionFailureKind::Other(msg.clone()),
+        err => ExecutionFailureKind::FunctionCall(err.clone()),
+    }
+}
+
+/// Grab the panic message of a function call error. The runtime reports panics either as
+/// a [`HostError::GuestPanic`] or as the display of one within an execution error.
+fn function_call_panic(err: &FunctionCallError) -> Option<&str> {
+    match err {
+        FunctionCallError::HostError(HostError::GuestPanic { panic_msg }) => {
+            Some(panic_msg.as_str())
+        }
+        FunctionCallError::ExecutionError(msg) => msg.strip_prefix(PANIC_PREFIX),
+        _ => None,
+    }
+}
+
+fn panic_message(err: &TxExecutionError) -> Option<&str> {
+    match action_error_kind(err)? {
+        ActionErrorKind::FunctionCallError(err) => function_call_panic(err),
+        _ => None,
+    }
+}
+
+fn action_error_kind(err: &TxExecutionError) -> Option<&ActionErrorKind> {
+    match err {
+        TxExecutionError::ActionError(err) => Some(&err.kind),
+        TxExecutionError::InvalidTxError(_) => None,
+    }
+}
+
+impl ExecutionFailure {
+    /// The message the contract panicked with, if the failure was caused by a contract panic.
+    pub fn panic_message(&self) -> Option<&str> {
+        panic_message(&self.value)
+    }
+
+    /// The kind of the action error that caused this failure. Returns `None` if the
+    /// transaction itself was invalid.
+    pub fn action_error_kind(&self) -> Option<&ActionErrorKind> {
+        action_error_kind(&self.value)
+    }
+
+    /// The reason for this failure, mapped to one of the common [`ExecutionFailureKind`]s.
+    pub fn failure_kind(&self) -> ExecutionFailureKind {
+        ExecutionFailureKind::from(&self.value)
+    }
+
+    /// Grab the outcome of the first transaction or receipt that failed. This is the
+    /// transaction itself when it failed before spawning any receipt.
+    pub fn failed_receipt(&self) -> Option<&ExecutionOutcome> {
+        self.details.failures().into_iter().next()
+    }
+}
+
+impl ExecutionOutcome {
+    fn failure(&self) -> Option<&TxExecutionError> {
+        match &self.status {
+            ExecutionStatusView::Failure(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// The message the contract panicked with, if this outcome failed due to a contract panic.
+    pub fn panic_message(&self) -> Option<&str> {
+        self.failure().and_then(panic_message)
+    }
+
+    /// The kind of the action error this outcome failed with, if any.
+    pub fn action_error_kind(&self) -> Option<&ActionErrorKind> {
+        self.failure().and_then(action_error_kind)
+    }
+
+    /// The reason this outcome failed, mapped to one of the common [`ExecutionFailureKind`]s.
+    /// Returns `None` if this outcome did not fail.
+    pub fn failure_kind(&self) -> Option<ExecutionFailureKind> {
+        self.failure().map(ExecutionFailureKind::from)
+    }
+
+    /// Returns this outcome if it failed. This allows treating outcomes the same as an
+    /// [`ExecutionFailure`] when looking for the failure.
+    pub fn failed_receipt(&self) -> Option<&ExecutionOutcome> {
+        self.failure().map(|_| self)
+    }
+}
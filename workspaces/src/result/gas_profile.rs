@@ -0,0 +1,136 @@
+//! Breakdown of the gas burnt by an execution per cost.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use near_primitives::views::CostGasUsed;
+
+use super::ExecutionDetails;
+use crate::types::Gas;
+
+/// Gas burnt by an execution, broken down per cost such as wasm instructions, storage
+/// reads and writes, or the creation of promises. This is reported by the network within
+/// the metadata of each receipt outcome.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasProfile {
+    entries: Vec<GasProfileEntry>,
+}
+
+/// Gas burnt for a single cost within a [`GasProfile`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GasProfileEntry {
+    /// Category of the cost, either `ACTION_COST` or `WASM_HOST_COST`.
+    pub category: String,
+    /// Name of the cost, e.g. `WASM_INSTRUCTION` or `STORAGE_WRITE_BASE`.
+    pub cost: String,
+    /// Gas burnt for this cost.
+    pub gas_used: Gas,
+}
+
+impl GasProfile {
+    /// Build a profile out of the entries, summing up the gas of duplicate costs.
+    fn from_entries<'a>(entries: impl IntoIterator<Item = &'a GasProfileEntry>) -> Self {
+        let mut costs: BTreeMap<(&str, &str), u64> = BTreeMap::new();
+        for entry in entries {
+            *costs
+                .entry((entry.category.as_str(), entry.cost.as_str()))
+                .or_default() += entry.gas_used.as_gas();
+        }
+
+        let mut entries: Vec<_> = costs
+            .into_iter()
+            .map(|((category, cost), gas_used)| GasProfileEntry {
+                category: category.to_string(),
+                cost: cost.to_string(),
+                gas_used: Gas::from_gas(gas_used),
+            })
+            .collect();
+        // Most expensive costs first, as those are usually the ones of interest.
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.gas_used));
+        Self { entries }
+    }
+
+    /// All the costs in this profile, ordered from the most to the least gas burnt.
+    pub fn entries(&self) -> &[GasProfileEntry] {
+        &self.entries
+    }
+
+    /// Gas burnt for the cost named `cost`, or zero if it was not incurred.
+    pub fn get(&self, cost: &str) -> Gas {
+        Gas::from_gas(
+            self.entries
+                .iter()
+                .filter(|entry| entry.cost == cost)
+                .map(|entry| entry.gas_used.as_gas())
+                .sum(),
+        )
+    }
+
+    /// Total gas burnt per cost category.
+    pub fn by_category(&self) -> BTreeMap<String, Gas> {
+        let mut categories = BTreeMap::new();
+        for entry in &self.entries {
+            let gas: &mut Gas = categories
+                .entry(entry.category.clone())
+                .or_insert_with(|| Gas::from_gas(0));
+            *gas = Gas::from_gas(gas.as_gas() + entry.gas_used.as_gas());
+        }
+        categories
+    }
+
+    /// Total gas accounted for by this profile. This does not include the base costs of
+    /// receipts and transactions, which are not broken down by the network.
+    pub fn total(&self) -> Gas {
+        Gas::from_gas(
+            self.entries
+                .iter()
+                .map(|entry| entry.gas_used.as_gas())
+                .sum(),
+        )
+    }
+
+    /// Whether no costs were reported at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for GasProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:<16} {:<40} {}",
+                entry.category, entry.cost, entry.gas_used
+            )?;
+        }
+        write!(f, "{:<16} {:<40} {}", "", "TOTAL", self.total())
+    }
+}
+
+impl From<Vec<CostGasUsed>> for GasProfile {
+    fn from(costs: Vec<CostGasUsed>) -> Self {
+        let entries: Vec<_> = costs
+            .into_iter()
+            .map(|cost| GasProfileEntry {
+                category: cost.cost_category,
+                cost: cost.cost,
+                gas_used: Gas::from_gas(cost.gas_used),
+            })
+            .collect();
+        Self::from_entries(&entries)
+    }
+}
+
+impl ExecutionDetails {
+    /// Aggregate the gas profiles of all the outcomes of this execution.
+    pub fn gas_profile(&self) -> GasProfile {
+        GasProfile::from_entries(
+            self.outcomes()
+                .into_iter()
+                .filter_map(|outcome| outcome.gas_profile.as_ref())
+                .flat_map(|profile| profile.entries()),
+        )
+    }
+}
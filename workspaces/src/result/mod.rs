@@ -1,5 +1,14 @@
 //! Result and execution types from results of RPC calls to the network.
 
+mod assertions;
+mod balance_diff;
+mod events;
+mod failure;
+mod gas_profile;
+mod state_diff;
+mod tree;
+mod upgrade;
+
 use std::fmt;
 
 use base64::{engine::general_purpose, Engine as _};
@@ -9,13 +18,26 @@ use near_gas::NearGas;
 use near_primitives::borsh;
 use near_primitives::errors::TxExecutionError;
 use near_primitives::views::{
-    CallResult, ExecutionOutcomeWithIdView, ExecutionStatusView, FinalExecutionOutcomeView,
-    FinalExecutionStatus,
+    CallResult, ExecutionOutcomeWithIdView, ExecutionStatusView,
+    FinalExecutionOutcomeWithReceiptView, FinalExecutionStatus, ReceiptView, SignedTransactionView,
 };
 
 use crate::error::ErrorKind;
 use crate::types::{CryptoHash, Gas, NearToken};
 
+pub use self::balance_diff::{AccountBalance, AccountBalanceDiff, BalanceDiff};
+pub(crate) use self::events::dec_format;
+pub use self::events::{
+    ContractMetadataUpdate, Event, FtBurn, FtMint, FtTransfer, Nep141Event, Nep171Event, NftBurn,
+    NftMint, NftTransfer, StandardEvent, EVENT_JSON_PREFIX,
+};
+pub use self::failure::ExecutionFailureKind;
+pub use self::gas_profile::{GasProfile, GasProfileEntry};
+pub(crate) use self::state_diff::display_key;
+pub use self::state_diff::{StateChange, StateDiff};
+pub use self::tree::{ReceiptNode, ReceiptTree};
+pub use self::upgrade::{UpgradeResult, UpgradeStep};
+
 pub type Result<T, E = crate::error::Error> = core::result::Result<T, E>;
 
 /// Execution related info as a result of performing a successful transaction
@@ -72,6 +94,10 @@ impl<T> Execution<T> {
 pub(crate) struct ExecutionDetails {
     pub(crate) transaction: ExecutionOutcome,
     pub(crate) receipts: Vec<ExecutionOutcome>,
+    /// The signed transaction that was sent, if it is known.
+    pub(crate) signed_transaction: Option<SignedTransactionView>,
+    /// The receipts spawned by the transaction, as reported by `EXPERIMENTAL_tx_status`.
+    pub(crate) receipt_views: Vec<ReceiptView>,
 }
 
 impl ExecutionDetails {
@@ -121,6 +147,10 @@ impl ExecutionDetails {
             .map(String::as_str)
             .collect()
     }
+    /// Build the tree of the transaction and the receipts it spawned.
+    pub fn receipt_tree(&self) -> ReceiptTree {
+        ReceiptTree::new(self)
+    }
 }
 
 /// The result after evaluating the status of an execution. This can be [`ExecutionSuccess`]
@@ -162,6 +192,8 @@ pub struct ExecutionFinalResult {
 
     pub(crate) status: FinalExecutionStatus,
     pub(crate) details: ExecutionDetails,
+    pub(crate) allowance_used: Option<NearToken>,
+    pub(crate) balance_diff: Option<BalanceDiff>,
 }
 
 impl fmt::Debug for ExecutionFinalResult {
@@ -176,7 +208,11 @@ impl fmt::Debug for ExecutionFinalResult {
 }
 
 impl ExecutionFinalResult {
-    pub(crate) fn from_view(view: FinalExecutionOutcomeView) -> Self {
+    pub(crate) fn from_view(view: FinalExecutionOutcomeWithReceiptView) -> Self {
+        let FinalExecutionOutcomeWithReceiptView {
+            final_outcome: view,
+            receipts: receipt_views,
+        } = view;
         let total_gas_burnt = view.transaction_outcome.outcome.gas_burnt
             + view
                 .receipts_outcome
@@ -184,6 +220,7 @@ impl ExecutionFinalResult {
                 .map(|t| t.outcome.gas_burnt)
                 .sum::<u64>();
 
+        let signed_transaction = Some(view.transaction);
         let transaction = view.transaction_outcome.into();
         let receipts = view
             .receipts_outcome
@@ -198,7 +235,11 @@ impl ExecutionFinalResult {
             details: ExecutionDetails {
                 transaction,
                 receipts,
+                signed_transaction,
+                receipt_views,
             },
+            allowance_used: None,
+            balance_diff: None,
         }
     }
 
@@ -310,6 +351,61 @@ impl ExecutionFinalResult {
     pub fn logs(&self) -> Vec<&str> {
         self.details.logs()
     }
+
+    /// Grab the tree of the transaction and the receipts it spawned, which keeps track of
+    /// which receipt spawned which. Its [`Display`](fmt::Display) implementation pretty
+    /// prints the whole call graph, which is useful for debugging.
+    pub fn receipt_tree(&self) -> ReceiptTree {
+        self.details.receipt_tree()
+    }
+
+    /// Grab the gas burnt by the transaction and all of its receipts, broken down per cost.
+    /// This helps figuring out why an execution got more expensive.
+    pub fn gas_profile(&self) -> GasProfile {
+        self.details.gas_profile()
+    }
+
+    /// Grab all the well formed [NEP-297](https://nomicon.io/Standards/EventsFormat) events
+    /// emitted through the logs of both the transaction and receipt outcomes.
+    pub fn events(&self) -> Vec<Event> {
+        self.details.events()
+    }
+
+    /// Grab the data of all the events of type `event` from the standard `standard`,
+    /// deserialized into instances of type `T`.
+    pub fn events_of<T: serde::de::DeserializeOwned>(
+        &self,
+        standard: &str,
+        event: &str,
+    ) -> Result<Vec<T>> {
+        self.details.events_of(standard, event)
+    }
+
+    /// Grab all the events of the standard `E`, such as [`Nep141Event`] or [`Nep171Event`].
+    /// Events of a type `E` has no variant for are skipped.
+    pub fn typed_events<E: StandardEvent>(&self) -> Result<Vec<E>> {
+        self.details.typed_events()
+    }
+
+    /// The amount of allowance consumed from the function call access key that signed
+    /// this transaction. This is only available for calls signed through
+    /// [`CallTransaction::sign_with`] or made by an account from [`Account::with_key`],
+    /// with a key that has a limited allowance. Batch transactions do not report it.
+    ///
+    /// [`CallTransaction::sign_with`]: crate::operations::CallTransaction::sign_with
+    /// [`Account::with_key`]: crate::Account::with_key
+    pub fn allowance_used(&self) -> Option<NearToken> {
+        self.allowance_used
+    }
+
+    /// Changes in the balances and storage of every account touched by this transaction.
+    /// This is only available when the transaction was sent with `with_balance_diff()`,
+    /// such as through [`CallTransaction::with_balance_diff`].
+    ///
+    /// [`CallTransaction::with_balance_diff`]: crate::operations::CallTransaction::with_balance_diff
+    pub fn balance_diff(&self) -> Option<&BalanceDiff> {
+        self.balance_diff.as_ref()
+    }
 }
 
 impl ExecutionSuccess {
@@ -369,6 +465,119 @@ impl<T> ExecutionResult<T> {
     pub fn logs(&self) -> Vec<&str> {
         self.details.logs()
     }
+
+    /// Grab the tree of the transaction and the receipts it spawned, which keeps track of
+    /// which receipt spawned which. Its [`Display`](fmt::Display) implementation pretty
+    /// prints the whole call graph, which is useful for debugging.
+    pub fn receipt_tree(&self) -> ReceiptTree {
+        self.details.receipt_tree()
+    }
+
+    /// Grab the gas burnt by the transaction and all of its receipts, broken down per cost.
+    /// This helps figuring out why an execution got more expensive.
+    pub fn gas_profile(&self) -> GasProfile {
+        self.details.gas_profile()
+    }
+
+    /// Grab all the well formed [NEP-297](https://nomicon.io/Standards/EventsFormat) events
+    /// emitted through the logs of both the transaction and receipt outcomes.
+    pub fn events(&self) -> Vec<Event> {
+        self.details.events()
+    }
+
+    /// Grab the data of all the events of type `event` from the standard `standard`,
+    /// deserialized into instances of type `D`.
+    pub fn events_of<D: serde::de::DeserializeOwned>(
+        &self,
+        standard: &str,
+        event: &str,
+    ) -> Result<Vec<D>> {
+        self.details.events_of(standard, event)
+    }
+
+    /// Grab all the events of the standard `E`, such as [`Nep141Event`] or [`Nep171Event`].
+    /// Events of a type `E` has no variant for are skipped.
+    pub fn typed_events<E: StandardEvent>(&self) -> Result<Vec<E>> {
+        self.details.typed_events()
+    }
+}
+
+/// Estimated costs of executing a transaction, found by dry-running it through
+/// [`Transaction::estimate`] or [`CallTransaction::estimate`]. The dry-run is reverted on
+/// sandbox, and happens in a throwaway sandbox on other networks, so the transaction never
+/// takes effect on the network it was estimated against.
+///
+/// [`Transaction::estimate`]: crate::operations::Transaction::estimate
+/// [`CallTransaction::estimate`]: crate::operations::CallTransaction::estimate
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct GasEstimate {
+    /// Total gas burnt by the transaction and all the receipts it generated.
+    pub total_gas_burnt: Gas,
+    /// Total amount of tokens burnt to pay for the gas.
+    pub tokens_burnt: NearToken,
+    /// Costs of the transaction followed by each of the receipts it generated.
+    pub outcomes: Vec<OutcomeEstimate>,
+    pub(crate) result: ExecutionFinalResult,
+}
+
+/// Estimated costs of a single transaction or receipt outcome within a [`GasEstimate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OutcomeEstimate {
+    /// The account the transaction or receipt was executed on.
+    pub executor_id: AccountId,
+    /// The amount of gas burnt by this transaction or receipt.
+    pub gas_burnt: Gas,
+    /// The amount of tokens burnt to pay for `gas_burnt`.
+    pub tokens_burnt: NearToken,
+}
+
+impl GasEstimate {
+    /// Build an estimate from the result of a dry-run. If `gas_price` is supplied, tokens
+    /// burnt are priced with it instead of the gas price of the network the dry-run
+    /// happened on.
+    pub(crate) fn new(result: ExecutionFinalResult, gas_price: Option<NearToken>) -> Self {
+        let outcomes: Vec<_> = result
+            .outcomes()
+            .into_iter()
+            .map(|outcome| OutcomeEstimate {
+                executor_id: outcome.executor_id.clone(),
+                gas_burnt: outcome.gas_burnt,
+                tokens_burnt: match gas_price {
+                    Some(price) => NearToken::from_yoctonear(
+                        price.as_yoctonear() * outcome.gas_burnt.as_gas() as u128,
+                    ),
+                    None => outcome.tokens_burnt,
+                },
+            })
+            .collect();
+
+        let tokens_burnt = NearToken::from_yoctonear(
+            outcomes
+                .iter()
+                .map(|outcome| outcome.tokens_burnt.as_yoctonear())
+                .sum(),
+        );
+
+        Self {
+            total_gas_burnt: result.total_gas_burnt,
+            tokens_burnt,
+            outcomes,
+            result,
+        }
+    }
+
+    /// The result of the dry-run execution, which can be used to inspect logs, failures
+    /// and the values returned by the transaction.
+    pub fn result(&self) -> &ExecutionFinalResult {
+        &self.result
+    }
+
+    /// Checks whether the dry-run of the transaction was successful.
+    pub fn is_success(&self) -> bool {
+        self.result.is_success()
+    }
 }
 
 /// The result from a call into a View function. This contains the contents or
@@ -432,6 +641,9 @@ pub struct ExecutionOutcome {
     /// The id of the account on which the execution happens. For transaction this is signer_id,
     /// for receipt this is receiver_id.
     pub executor_id: AccountId,
+    /// Gas burnt by this transaction or receipt, broken down per cost. This is only
+    /// available when reported by the network, which is not the case for transactions.
+    pub gas_profile: Option<GasProfile>,
     /// Execution status. Contains the result in case of successful execution.
     pub(crate) status: ExecutionStatusView,
 }
@@ -542,6 +754,7 @@ impl From<ExecutionOutcomeWithIdView> for ExecutionOutcome {
             gas_burnt: NearGas::from_gas(view.outcome.gas_burnt),
             tokens_burnt: NearToken::from_yoctonear(view.outcome.tokens_burnt),
             executor_id: view.outcome.executor_id,
+            gas_profile: view.outcome.metadata.gas_profile.map(GasProfile::from),
             status: view.outcome.status,
         }
     }
@@ -0,0 +1,108 @@
+//! Changes in the raw state of a contract.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Changes between two snapshots of the raw key value state of a contract, as returned by
+/// [`Contract::view_state`]. Keys are ordered bytewise within each kind of change.
+///
+/// [`Contract::view_state`]: crate::Contract::view_state
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    added: BTreeMap<Vec<u8>, Vec<u8>>,
+    removed: BTreeMap<Vec<u8>, Vec<u8>>,
+    changed: BTreeMap<Vec<u8>, StateChange>,
+}
+
+/// Value of a key before and after a change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StateChange {
+    /// Raw value of the key before the change.
+    pub before: Vec<u8>,
+    /// Raw value of the key after the change.
+    pub after: Vec<u8>,
+}
+
+impl StateDiff {
+    /// Compare the state `before` with the state `after`.
+    pub fn new(before: &HashMap<Vec<u8>, Vec<u8>>, after: &HashMap<Vec<u8>, Vec<u8>>) -> Self {
+        let mut diff = Self::default();
+        for (key, value) in before {
+            match after.get(key) {
+                None => {
+                    diff.removed.insert(key.clone(), value.clone());
+                }
+                Some(new) if new != value => {
+                    let change = StateChange {
+                        before: value.clone(),
+                        after: new.clone(),
+                    };
+                    diff.changed.insert(key.clone(), change);
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, value) in after {
+            if !before.contains_key(key) {
+                diff.added.insert(key.clone(), value.clone());
+            }
+        }
+        diff
+    }
+
+    /// Keys that did not exist before, along with their new value.
+    pub fn added(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
+        &self.added
+    }
+
+    /// Keys that no longer exist, along with their previous value.
+    pub fn removed(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
+        &self.removed
+    }
+
+    /// Keys whose value changed.
+    pub fn changed(&self) -> &BTreeMap<Vec<u8>, StateChange> {
+        &self.changed
+    }
+
+    /// Whether the state did not change at all.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no state changes");
+        }
+
+        write!(
+            f,
+            "{} added, {} removed, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )?;
+        for key in self.added.keys() {
+            write!(f, "\n  + {}", display_key(key))?;
+        }
+        for key in self.removed.keys() {
+            write!(f, "\n  - {}", display_key(key))?;
+        }
+        for key in self.changed.keys() {
+            write!(f, "\n  ~ {}", display_key(key))?;
+        }
+        Ok(())
+    }
+}
+
+/// Show keys as text where possible, since most contracts use readable prefixes such as
+/// `STATE`, and escape any other byte.
+pub(crate) fn display_key(key: &[u8]) -> String {
+    key.iter()
+        .flat_map(|byte| std::ascii::escape_default(*byte))
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,292 @@
+//! Tree of a transaction and the receipts it spawned during its execution.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use near_account_id::AccountId;
+use near_primitives::views::{ActionView, ExecutionStatusView, ReceiptEnumView};
+
+use super::{ExecutionDetails, ExecutionOutcome};
+use crate::types::{CryptoHash, Gas, NearToken};
+
+/// Tree of a transaction and the receipts it spawned, keyed by the ids found in
+/// [`ExecutionOutcome::receipt_ids`]. The root of the tree is the transaction itself.
+///
+/// The [`Display`](fmt::Display) implementation pretty prints the whole call graph:
+///
+/// ```text
+/// Transaction 9cqK…: alice.test.near -> bob.test.near: FunctionCall(ping) [SUCCESS] 2.4 Tgas
+/// └── Receipt 4Fjr…: bob.test.near from alice.test.near: FunctionCall(ping) [SUCCESS] 3.1 Tgas
+///     │ log: pinged
+///     └── Receipt Hn2P…: alice.test.near from system: Transfer(0.0001 NEAR) [SUCCESS] 0 gas
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReceiptTree {
+    root: CryptoHash,
+    nodes: HashMap<CryptoHash, ReceiptNode>,
+}
+
+/// A single transaction or receipt within a [`ReceiptTree`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReceiptNode {
+    /// The id of the receipt, or the hash of the transaction for the root node.
+    pub id: CryptoHash,
+    /// The account the transaction or receipt was executed on.
+    pub executor_id: AccountId,
+    /// The account the transaction or receipt is addressed to. For receipts, this is the
+    /// same as `executor_id`.
+    pub receiver_id: AccountId,
+    /// The account that spawned this transaction or receipt, which is the signer for the
+    /// transaction and `system` for gas refunds. `None` if the RPC did not report it.
+    pub predecessor_id: Option<AccountId>,
+    /// Actions carried by the transaction or receipt. `None` if the RPC did not report them.
+    pub actions: Option<Vec<ActionView>>,
+    /// Logs emitted while executing this transaction or receipt.
+    pub logs: Vec<String>,
+    /// The amount of gas burnt by this transaction or receipt.
+    pub gas_burnt: Gas,
+    /// The amount of tokens burnt corresponding to the burnt gas amount.
+    pub tokens_burnt: NearToken,
+    /// The id of the node that spawned this one. `None` for the transaction.
+    pub parent: Option<CryptoHash>,
+    /// Ids of the receipts spawned by this node, in the order they were created.
+    pub children: Vec<CryptoHash>,
+    /// The execution status of this transaction or receipt.
+    pub status: ExecutionStatusView,
+}
+
+impl ReceiptTree {
+    pub(crate) fn new(details: &ExecutionDetails) -> Self {
+        let transaction = &details.transaction;
+        let mut nodes = HashMap::new();
+        let root = match &details.signed_transaction {
+            Some(tx) => ReceiptNode::new(
+                transaction,
+                tx.receiver_id.clone(),
+                Some(tx.signer_id.clone()),
+                Some(tx.actions.clone()),
+            ),
+            None => ReceiptNode::new(transaction, transaction.executor_id.clone(), None, None),
+        };
+        nodes.insert(transaction.transaction_hash, root);
+
+        let receipts: HashMap<_, _> = details
+            .receipt_views
+            .iter()
+            .map(|receipt| (CryptoHash(receipt.receipt_id.0), receipt))
+            .collect();
+        for outcome in details.receipt_outcomes() {
+            let node = match receipts.get(&outcome.transaction_hash) {
+                Some(receipt) => {
+                    let actions = match &receipt.receipt {
+                        ReceiptEnumView::Action { actions, .. } => Some(actions.clone()),
+                        ReceiptEnumView::Data { .. } => None,
+                    };
+                    ReceiptNode::new(
+                        outcome,
+                        receipt.receiver_id.clone(),
+                        Some(receipt.predecessor_id.clone()),
+                        actions,
+                    )
+                }
+                None => ReceiptNode::new(outcome, outcome.executor_id.clone(), None, None),
+            };
+            nodes.insert(outcome.transaction_hash, node);
+        }
+
+        let mut parents = HashMap::new();
+        for outcome in details.outcomes() {
+            for child in &outcome.receipt_ids {
+                parents.insert(*child, outcome.transaction_hash);
+            }
+        }
+        for node in nodes.values_mut() {
+            node.parent = parents.get(&node.id).copied();
+        }
+
+        // Drop references to receipts which have not been executed yet.
+        let known: Vec<CryptoHash> = nodes.keys().copied().collect();
+        for node in nodes.values_mut() {
+            node.children.retain(|child| known.contains(child));
+        }
+
+        Self {
+            root: transaction.transaction_hash,
+            nodes,
+        }
+    }
+
+    /// The root of the tree, which is the transaction itself.
+    pub fn root(&self) -> &ReceiptNode {
+        &self.nodes[&self.root]
+    }
+
+    /// Grab the node of the transaction or receipt with the given id.
+    pub fn get(&self, id: &CryptoHash) -> Option<&ReceiptNode> {
+        self.nodes.get(id)
+    }
+
+    /// Grab the node that spawned `node`. Returns `None` for the root of the tree.
+    pub fn parent(&self, node: &ReceiptNode) -> Option<&ReceiptNode> {
+        node.parent.as_ref().and_then(|id| self.nodes.get(id))
+    }
+
+    /// Grab the nodes spawned by `node`, in the order they were created.
+    pub fn children(&self, node: &ReceiptNode) -> Vec<&ReceiptNode> {
+        node.children
+            .iter()
+            .filter_map(|id| self.nodes.get(id))
+            .collect()
+    }
+
+    /// Grab all the nodes of the tree in depth-first order, starting from the root.
+    pub fn nodes(&self) -> Vec<&ReceiptNode> {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![self.root()];
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            stack.extend(self.children(node).into_iter().rev());
+        }
+        nodes
+    }
+
+    /// The number of nodes in the tree, including the transaction.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the tree is empty. A tree always contains at least the transaction,
+    /// so this is always `false`.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn fmt_node(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        node: &ReceiptNode,
+        prefix: &str,
+        is_last: bool,
+    ) -> fmt::Result {
+        let (branch, child_prefix) = match (node.parent.is_some(), is_last) {
+            (false, _) => ("", prefix.to_string()),
+            (true, true) => ("└── ", format!("{prefix}    ")),
+            (true, false) => ("├── ", format!("{prefix}│   ")),
+        };
+        writeln!(f, "{prefix}{branch}{node}")?;
+
+        let children = self.children(node);
+        let log_prefix = if children.is_empty() { "  " } else { "│ " };
+        for log in &node.logs {
+            writeln!(f, "{child_prefix}{log_prefix}log: {log}")?;
+        }
+
+        for (i, child) in children.iter().enumerate() {
+            self.fmt_node(f, child, &child_prefix, i + 1 == children.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ReceiptTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_node(f, self.root(), "", true)
+    }
+}
+
+impl ReceiptNode {
+    fn new(
+        outcome: &ExecutionOutcome,
+        receiver_id: AccountId,
+        predecessor_id: Option<AccountId>,
+        actions: Option<Vec<ActionView>>,
+    ) -> Self {
+        Self {
+            id: outcome.transaction_hash,
+            executor_id: outcome.executor_id.clone(),
+            receiver_id,
+            predecessor_id,
+            actions,
+            logs: outcome.logs.clone(),
+            gas_burnt: outcome.gas_burnt,
+            tokens_burnt: outcome.tokens_burnt,
+            parent: None,
+            children: outcome.receipt_ids.clone(),
+            status: outcome.status.clone(),
+        }
+    }
+
+    /// Whether this node is the transaction at the root of the tree.
+    pub fn is_transaction(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    /// Checks whether this transaction or receipt was executed successfully.
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self.status,
+            ExecutionStatusView::SuccessValue(_) | ExecutionStatusView::SuccessReceiptId(_)
+        )
+    }
+
+    /// Checks whether this transaction or receipt failed. Returns true if it failed with
+    /// an error or the execution state was unknown or pending.
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self.status,
+            ExecutionStatusView::Failure(_) | ExecutionStatusView::Unknown
+        )
+    }
+}
+
+impl fmt::Display for ReceiptNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_transaction() {
+            write!(
+                f,
+                "Transaction {}: {} -> {}",
+                self.id, self.executor_id, self.receiver_id
+            )?;
+        } else {
+            write!(f, "Receipt {}: {}", self.id, self.executor_id)?;
+            if let Some(predecessor_id) = &self.predecessor_id {
+                write!(f, " from {predecessor_id}")?;
+            }
+        }
+
+        if let Some(actions) = self.actions.as_ref().filter(|actions| !actions.is_empty()) {
+            let actions: Vec<_> = actions.iter().map(action_summary).collect();
+            write!(f, ": {}", actions.join(", "))?;
+        }
+
+        match &self.status {
+            ExecutionStatusView::SuccessValue(_) | ExecutionStatusView::SuccessReceiptId(_) => {
+                write!(f, " [SUCCESS]")?
+            }
+            ExecutionStatusView::Failure(err) => write!(f, " [FAILURE: {err}]")?,
+            ExecutionStatusView::Unknown => write!(f, " [UNKNOWN]")?,
+        }
+        write!(f, " {}", self.gas_burnt)
+    }
+}
+
+/// Short, single line description of an action.
+fn action_summary(action: &ActionView) -> String {
+    match action {
+        ActionView::FunctionCall { method_name, .. } => format!("FunctionCall({method_name})"),
+        ActionView::Transfer { deposit } => {
+            format!("Transfer({})", NearToken::from_yoctonear(*deposit))
+        }
+        ActionView::DeployContract { .. } => "DeployContract".to_string(),
+        action => {
+            // Only keep the name of the variant, as the rest can be arbitrarily large.
+            let debug = format!("{action:?}");
+            debug
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        }
+    }
+}
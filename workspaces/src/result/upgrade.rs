@@ -0,0 +1,111 @@
+//! Outcome of upgrading a contract through [`Contract::upgrade`].
+//!
+//! [`Contract::upgrade`]: crate::Contract::upgrade
+
+use std::fmt;
+
+use near_primitives::errors::{ActionErrorKind, FunctionCallError, TxExecutionError};
+use near_primitives::views::{ExecutionStatusView, FinalExecutionStatus};
+
+use super::{ExecutionFinalResult, StateDiff};
+use crate::error::ErrorKind;
+use crate::result::Result;
+use crate::types::ContractState;
+
+/// A step of an upgrade, which is sent as a single batch transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpgradeStep {
+    /// The transaction was rejected before any of its actions ran.
+    Transaction,
+    /// Deploying the new code, which includes the new code failing to compile or lacking
+    /// the migration method.
+    Deploy,
+    /// Calling the migration method on the new code.
+    Migrate,
+}
+
+impl fmt::Display for UpgradeStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transaction => write!(f, "transaction"),
+            Self::Deploy => write!(f, "deploy"),
+            Self::Migrate => write!(f, "migrate"),
+        }
+    }
+}
+
+/// Outcome of an upgrade, along with the raw state of the contract before and after it.
+/// Since the deploy and the migration are sent as one batch transaction, either both are
+/// applied or none, in which case the state is left untouched.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct UpgradeResult {
+    /// Execution details of the upgrade transaction.
+    pub details: ExecutionFinalResult,
+    /// Raw state of the contract before the upgrade.
+    pub state_before: ContractState,
+    /// Raw state of the contract after the upgrade.
+    pub state_after: ContractState,
+    /// Changes between `state_before` and `state_after`.
+    pub state_diff: StateDiff,
+}
+
+impl UpgradeResult {
+    /// Whether both the deploy and the migration succeeded.
+    pub fn is_success(&self) -> bool {
+        self.details.is_success()
+    }
+
+    /// The step the upgrade failed at, if it failed.
+    pub fn failed_step(&self) -> Option<UpgradeStep> {
+        match &self.details.status {
+            FinalExecutionStatus::Failure(TxExecutionError::InvalidTxError(_)) => {
+                return Some(UpgradeStep::Transaction)
+            }
+            FinalExecutionStatus::Failure(_) => {}
+            _ => return None,
+        }
+
+        // The deploy and the migration are the actions of the receipt of the upgrade
+        // transaction itself. Any later receipt comes from promises made by the migration.
+        let err = match self.details.receipt_outcomes().first().map(|o| &o.status) {
+            Some(ExecutionStatusView::Failure(TxExecutionError::ActionError(err))) => err,
+            Some(_) => return Some(UpgradeStep::Migrate),
+            None => return Some(UpgradeStep::Transaction),
+        };
+
+        // The deploy is the first action of the batch, the migration the second. Code only
+        // gets compiled once it is called though, so a broken deploy surfaces as the
+        // migration failing to compile or to resolve its method.
+        let broken_code = matches!(
+            err.kind,
+            ActionErrorKind::FunctionCallError(
+                FunctionCallError::CompilationError(_)
+                    | FunctionCallError::LinkError { .. }
+                    | FunctionCallError::MethodResolveError(_)
+            )
+        );
+        Some(match err.index {
+            Some(0) => UpgradeStep::Deploy,
+            Some(_) if broken_code => UpgradeStep::Deploy,
+            Some(_) => UpgradeStep::Migrate,
+            None => UpgradeStep::Transaction,
+        })
+    }
+
+    /// Grab the state diff of a successful upgrade, or an error naming the step the upgrade
+    /// failed at.
+    pub fn into_result(self) -> Result<StateDiff> {
+        let step = self.failed_step();
+        match self.details.into_result() {
+            Ok(_) => Ok(self.state_diff),
+            Err(failure) => Err(ErrorKind::Execution.full(
+                format!(
+                    "upgrade failed at the {} step: {failure}",
+                    step.unwrap_or(UpgradeStep::Transaction)
+                ),
+                failure,
+            )),
+        }
+    }
+}
@@ -18,12 +18,12 @@ use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeployContractAction,
-    FunctionCallAction, SignedTransaction, TransferAction,
+    SignedTransaction, TransferAction,
 };
-use near_primitives::types::{BlockReference, Finality, Gas};
+use near_primitives::types::{BlockReference, Finality};
 use near_primitives::views::{
-    AccessKeyView, BlockView, FinalExecutionOutcomeView, QueryRequest, StatusResponse,
-    TxExecutionStatus,
+    AccessKeyView, BlockView, FinalExecutionOutcomeViewEnum, FinalExecutionOutcomeWithReceiptView,
+    QueryRequest, StatusResponse, TxExecutionStatus,
 };
 
 #[cfg(feature = "experimental")]
@@ -39,7 +39,7 @@ use {
     },
 };
 
-use crate::error::{Error, ErrorKind, RpcErrorCode};
+use crate::error::{AccessKeyErrorCode, Error, ErrorKind, RpcErrorCode};
 use crate::operations::TransactionStatus;
 use crate::result::Result;
 use crate::types::{AccountId, InMemorySigner, Nonce, PublicKey};
@@ -48,6 +48,8 @@ use crate::{Network, Worker};
 pub(crate) const DEFAULT_CALL_FN_GAS: NearGas = NearGas::from_tgas(10);
 pub(crate) const DEFAULT_CALL_DEPOSIT: NearToken = NearToken::from_near(0);
 pub(crate) const DEFAULT_PRIORITY_FEE: u64 = 0;
+/// Execution level to wait for when sending a transaction, equivalent to `broadcast_tx_commit`.
+pub(crate) const DEFAULT_WAIT_UNTIL: TxExecutionStatus = TxExecutionStatus::ExecutedOptimistic;
 
 /// A client that wraps around [`JsonRpcClient`], and provides more capabilities such
 /// as retry w/ exponential backoff and utility functions for sending transactions.
@@ -56,6 +58,8 @@ pub struct Client {
     rpc_client: JsonRpcClient,
     /// AccessKey nonces to reference when sending transactions.
     pub(crate) access_key_nonces: RwLock<HashMap<(AccountId, near_crypto::PublicKey), AtomicU64>>,
+    /// Whether sending transactions through this client is refused.
+    read_only: bool,
 }
 
 impl Client {
@@ -72,13 +76,33 @@ impl Client {
             rpc_client,
             rpc_addr: rpc_addr.into(),
             access_key_nonces: RwLock::new(HashMap::new()),
+            read_only: false,
         })
     }
 
+    /// Refuse sending any transaction through this client, so it can only be used to query
+    /// the network.
+    pub(crate) fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Fail if this client is not allowed to send transactions.
+    fn ensure_writable(&self, receiver_id: &AccountId) -> Result<()> {
+        if self.read_only {
+            return Err(ErrorKind::ReadOnly.message(format!(
+                "refusing to send a transaction to {receiver_id} on {}, which is read-only; \
+                 enable transactions on the network builder with `allow_transactions()`",
+                self.rpc_addr
+            )));
+        }
+        Ok(())
+    }
+
     pub(crate) async fn query_broadcast_tx(
         &self,
-        method: &methods::broadcast_tx_commit::RpcBroadcastTxCommitRequest,
-    ) -> MethodCallResult<FinalExecutionOutcomeView, RpcTransactionError> {
+        method: &methods::send_tx::RpcSendTransactionRequest,
+    ) -> MethodCallResult<RpcTransactionResponse, RpcTransactionError> {
         retry(|| async {
             let result = self.rpc_client.call(method).await;
             match &result {
@@ -98,7 +122,7 @@ impl Client {
                             target: "workspaces",
                             "Submitting transaction with actions {:?} succeeded with status {:?}",
                             method.signed_transaction.transaction.actions(),
-                            response.status
+                            response.final_execution_status
                         );
                     }
                 }
@@ -147,31 +171,8 @@ impl Client {
         signer: &InMemorySigner,
         receiver_id: &AccountId,
         action: Action,
-    ) -> Result<FinalExecutionOutcomeView> {
-        send_batch_tx_and_retry(self, signer, receiver_id, vec![action]).await
-    }
-
-    pub(crate) async fn call(
-        &self,
-        signer: &InMemorySigner,
-        contract_id: &AccountId,
-        method_name: String,
-        args: Vec<u8>,
-        gas: Gas,
-        deposit: NearToken,
-    ) -> Result<FinalExecutionOutcomeView> {
-        self.send_tx_and_retry(
-            signer,
-            contract_id,
-            FunctionCallAction {
-                args,
-                method_name,
-                gas,
-                deposit: deposit.as_yoctonear(),
-            }
-            .into(),
-        )
-        .await
+    ) -> Result<FinalExecutionOutcomeWithReceiptView> {
+        send_batch_tx_and_retry(self, signer, receiver_id, vec![action], DEFAULT_WAIT_UNTIL).await
     }
 
     pub(crate) async fn view_block(&self, block_ref: Option<BlockReference>) -> Result<BlockView> {
@@ -189,7 +190,7 @@ impl Client {
         signer: &InMemorySigner,
         contract_id: &AccountId,
         wasm: Vec<u8>,
-    ) -> Result<FinalExecutionOutcomeView> {
+    ) -> Result<FinalExecutionOutcomeWithReceiptView> {
         self.send_tx_and_retry(
             signer,
             contract_id,
@@ -203,7 +204,7 @@ impl Client {
         signer: &InMemorySigner,
         receiver_id: &AccountId,
         amount_yocto: NearToken,
-    ) -> Result<FinalExecutionOutcomeView> {
+    ) -> Result<FinalExecutionOutcomeWithReceiptView> {
         self.send_tx_and_retry(
             signer,
             receiver_id,
@@ -221,7 +222,8 @@ impl Client {
         new_account_id: &AccountId,
         new_account_pk: PublicKey,
         amount: NearToken,
-    ) -> Result<FinalExecutionOutcomeView> {
+        wait_until: TxExecutionStatus,
+    ) -> Result<FinalExecutionOutcomeWithReceiptView> {
         send_batch_tx_and_retry(
             self,
             signer,
@@ -241,6 +243,7 @@ impl Client {
                 }
                 .into(),
             ],
+            wait_until,
         )
         .await
     }
@@ -252,7 +255,7 @@ impl Client {
         new_account_pk: PublicKey,
         amount: NearToken,
         code: Vec<u8>,
-    ) -> Result<FinalExecutionOutcomeView> {
+    ) -> Result<FinalExecutionOutcomeWithReceiptView> {
         send_batch_tx_and_retry(
             self,
             signer,
@@ -273,6 +276,7 @@ impl Client {
                 .into(),
                 DeployContractAction { code }.into(),
             ],
+            DEFAULT_WAIT_UNTIL,
         )
         .await
     }
@@ -282,7 +286,7 @@ impl Client {
         signer: &InMemorySigner,
         account_id: &AccountId,
         beneficiary_id: &AccountId,
-    ) -> Result<FinalExecutionOutcomeView> {
+    ) -> Result<FinalExecutionOutcomeWithReceiptView> {
         let beneficiary_id = beneficiary_id.to_owned();
         self.send_tx_and_retry(
             signer,
@@ -301,19 +305,23 @@ impl Client {
         Ok(result)
     }
 
+    /// Query the status of a transaction along with the receipts it spawned, which carry
+    /// who spawned each of them and with which actions.
     pub(crate) async fn tx_async_status(
         &self,
         sender_id: &AccountId,
         tx_hash: CryptoHash,
         wait_until: TxExecutionStatus,
     ) -> Result<RpcTransactionResponse, JsonRpcError<RpcTransactionError>> {
-        self.query(methods::tx::RpcTransactionStatusRequest {
-            transaction_info: methods::tx::TransactionInfo::TransactionId {
-                sender_account_id: sender_id.clone(),
-                tx_hash,
+        self.query(
+            methods::EXPERIMENTAL_tx_status::RpcTransactionStatusRequest {
+                transaction_info: methods::tx::TransactionInfo::TransactionId {
+                    sender_account_id: sender_id.clone(),
+                    tx_hash,
+                },
+                wait_until,
             },
-            wait_until,
-        })
+        )
         .await
     }
 
@@ -347,6 +355,53 @@ impl Client {
             })?;
         Ok(())
     }
+
+    /// Seed the nonce cache with the nonce of a newly added access key. This queries the
+    /// key once, right after it got added, so transactions signed with it start off the
+    /// nonce it was added with rather than racing each other to query it.
+    pub(crate) async fn seed_access_key_nonce(
+        &self,
+        account_id: &AccountId,
+        public_key: &near_crypto::PublicKey,
+    ) -> Result<()> {
+        let (access_key, _) = access_key(self, account_id.clone(), public_key.clone()).await?;
+        self.access_key_nonces
+            .write()
+            .await
+            .entry((account_id.clone(), public_key.clone()))
+            .or_insert_with(|| AtomicU64::new(access_key.nonce))
+            .fetch_max(access_key.nonce, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Drop the cached nonce of an access key that was deleted.
+    pub(crate) async fn forget_access_key_nonce(
+        &self,
+        account_id: &AccountId,
+        public_key: &near_crypto::PublicKey,
+    ) {
+        self.access_key_nonces
+            .write()
+            .await
+            .remove(&(account_id.clone(), public_key.clone()));
+    }
+
+    pub(crate) async fn max_contract_size(&self) -> Result<u64> {
+        let config = self
+            .rpc_client
+            .call(
+                methods::EXPERIMENTAL_protocol_config::RpcProtocolConfigRequest {
+                    block_reference: Finality::Final.into(),
+                },
+            )
+            .await
+            .map_err(|e| RpcErrorCode::QueryFailure.custom(e))?;
+        Ok(config
+            .runtime_config
+            .wasm_config
+            .limit_config
+            .max_contract_size)
+    }
 }
 
 #[cfg(feature = "experimental")]
@@ -533,10 +588,12 @@ pub(crate) async fn send_tx(
     client: &Client,
     cache_key: &(AccountId, near_crypto::PublicKey),
     tx: SignedTransaction,
-) -> Result<FinalExecutionOutcomeView> {
+    wait_until: TxExecutionStatus,
+) -> Result<FinalExecutionOutcomeViewEnum> {
     let result = client
-        .query_broadcast_tx(&methods::broadcast_tx_commit::RpcBroadcastTxCommitRequest {
+        .query_broadcast_tx(&methods::send_tx::RpcSendTransactionRequest {
             signed_transaction: tx,
+            wait_until,
         })
         .await;
 
@@ -552,7 +609,101 @@ pub(crate) async fn send_tx(
         nonces.remove(cache_key);
     }
 
-    result.map_err(|e| RpcErrorCode::BroadcastTxFailure.custom(e))
+    // The access key is not permitted to sign this transaction, so surface why instead of
+    // a generic broadcast failure.
+    if let Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+        RpcTransactionError::InvalidTransaction {
+            context: InvalidTxError::InvalidAccessKeyError(err),
+            ..
+        },
+    ))) = &result
+    {
+        return Err(AccessKeyErrorCode::from(err).custom(err.clone()));
+    }
+
+    let RpcTransactionResponse {
+        final_execution_outcome,
+        final_execution_status,
+    } = result.map_err(|e| RpcErrorCode::BroadcastTxFailure.custom(e))?;
+    final_execution_outcome.ok_or_else(|| {
+        RpcErrorCode::BroadcastTxFailure.message(format!(
+            "transaction reached {final_execution_status:?} without an execution outcome"
+        ))
+    })
+}
+
+/// Grab the receipts spawned by an executed transaction, unless `outcome` already comes
+/// with them. `send_tx` leaves them out, so they are queried separately. This is done
+/// outside of any retry around `send_tx`, which would otherwise send the transaction again.
+///
+/// The transaction is committed by then, so failing to query its receipts is not an error:
+/// the outcome is kept without them, which only leaves the receipt tree less detailed.
+async fn with_receipts(
+    client: &Client,
+    outcome: FinalExecutionOutcomeViewEnum,
+    wait_until: TxExecutionStatus,
+) -> FinalExecutionOutcomeWithReceiptView {
+    let outcome = match outcome {
+        FinalExecutionOutcomeViewEnum::FinalExecutionOutcomeWithReceipt(outcome) => return outcome,
+        FinalExecutionOutcomeViewEnum::FinalExecutionOutcome(outcome) => outcome,
+    };
+
+    let resp = client
+        .tx_async_status(
+            &outcome.transaction.signer_id,
+            outcome.transaction_outcome.id,
+            wait_until,
+        )
+        .await;
+    match resp.map(|resp| resp.final_execution_outcome) {
+        Ok(Some(FinalExecutionOutcomeViewEnum::FinalExecutionOutcomeWithReceipt(outcome))) => {
+            outcome
+        }
+        Ok(_) => {
+            tracing::warn!(
+                target: "workspaces",
+                "no receipts reported for transaction {}",
+                outcome.transaction_outcome.id
+            );
+            FinalExecutionOutcomeWithReceiptView {
+                final_outcome: outcome,
+                receipts: Vec::new(),
+            }
+        }
+        Err(err) => {
+            tracing::warn!(
+                target: "workspaces",
+                "could not query the receipts of transaction {}: {:?}",
+                outcome.transaction_outcome.id,
+                err
+            );
+            FinalExecutionOutcomeWithReceiptView {
+                final_outcome: outcome,
+                receipts: Vec::new(),
+            }
+        }
+    }
+}
+
+/// Whether waiting for a transaction up to `wait_until` yields its execution outcome.
+/// Levels that only guarantee inclusion into a block do not.
+pub(crate) fn has_execution_outcome(wait_until: &TxExecutionStatus) -> bool {
+    !matches!(
+        wait_until,
+        TxExecutionStatus::None | TxExecutionStatus::Included | TxExecutionStatus::IncludedFinal
+    )
+}
+
+/// Orders [`TxExecutionStatus`] by how far along the transaction is in its execution.
+pub(crate) fn execution_level(status: &TxExecutionStatus) -> u8 {
+    match status {
+        TxExecutionStatus::None => 0,
+        TxExecutionStatus::Included => 1,
+        TxExecutionStatus::ExecutedOptimistic => 2,
+        TxExecutionStatus::IncludedFinal => 3,
+        TxExecutionStatus::Executed => 4,
+        TxExecutionStatus::Final => 5,
+    }
 }
 
 pub(crate) async fn send_batch_tx_and_retry(
@@ -560,13 +711,22 @@ pub(crate) async fn send_batch_tx_and_retry(
     signer: &InMemorySigner,
     receiver_id: &AccountId,
     actions: Vec<Action>,
-) -> Result<FinalExecutionOutcomeView> {
+    wait_until: TxExecutionStatus,
+) -> Result<FinalExecutionOutcomeWithReceiptView> {
+    client.ensure_writable(receiver_id)?;
+    if !has_execution_outcome(&wait_until) {
+        return Err(ErrorKind::Usage.message(format!(
+            "`transact` cannot wait until {wait_until:?}, which does not provide an execution \
+             outcome; use `transact_async` to wait for the transaction to be included instead"
+        )));
+    }
+
     let inner = signer.inner();
     let cache_key = (
         signer.account_id.clone(),
         signer.secret_key.public_key().into(),
     );
-    retry(|| async {
+    let outcome = retry(|| async {
         let (block_hash, nonce) = fetch_tx_nonce(client, &cache_key).await?;
         send_tx(
             client,
@@ -580,10 +740,13 @@ pub(crate) async fn send_batch_tx_and_retry(
                 block_hash,
                 DEFAULT_PRIORITY_FEE,
             ),
+            wait_until.clone(),
         )
         .await
     })
-    .await
+    .await?;
+
+    Ok(with_receipts(client, outcome, wait_until).await)
 }
 
 pub(crate) async fn send_batch_tx_async_and_retry(
@@ -591,7 +754,9 @@ pub(crate) async fn send_batch_tx_async_and_retry(
     signer: &InMemorySigner,
     receiver_id: &AccountId,
     actions: Vec<Action>,
+    wait_until: TxExecutionStatus,
 ) -> Result<TransactionStatus> {
+    worker.client().ensure_writable(receiver_id)?;
     let inner = signer.inner();
     let cache_key = (
         signer.account_id.clone(),
@@ -619,6 +784,7 @@ pub(crate) async fn send_batch_tx_async_and_retry(
             worker.clone(),
             signer.account_id.clone(),
             hash,
+            wait_until.clone(),
         ))
     })
     .await
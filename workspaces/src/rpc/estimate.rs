@@ -0,0 +1,582 @@
+//! Dry-running transactions to estimate the gas and tokens they would burn.
+
+use std::collections::{BTreeSet, HashMap};
+
+use near_gas::NearGas;
+use near_primitives::borsh;
+use near_primitives::state_record::StateRecord;
+use near_primitives::transaction::{
+    Action, DeleteAccountAction, DeleteKeyAction, DeployContractAction, FunctionCallAction,
+};
+use near_primitives::views::FinalExecutionOutcomeWithReceiptView;
+
+use crate::network::Sandbox;
+use crate::result::{ExecutionFinalResult, GasEstimate, Result};
+use crate::rpc::client::{send_batch_tx_and_retry, Client, DEFAULT_WAIT_UNTIL};
+use crate::rpc::patch::patch_records;
+use crate::types::account::AccountDetails;
+use crate::types::{AccessKey, AccessKeyInfo, PublicKey};
+use crate::{AccountId, CryptoHash, InMemorySigner, Network, Worker};
+
+/// Maximum number of times a dry-run in the estimation sandbox is repeated, importing the
+/// accounts its receipts reached which were not imported yet.
+const MAX_IMPORT_ROUNDS: usize = 8;
+
+/// Gas attached to [`STORAGE_CLEANER`] calls.
+const CLEANUP_GAS: NearGas = NearGas::from_tgas(250);
+
+/// Contract removing the storage keys it is called with, in place of the code of accounts
+/// which got storage added by a dry-run. Its `clean` method takes the borsh serialized
+/// `Vec<Vec<u8>>` of keys to remove. Assembled from:
+///
+/// ```wat
+/// (module
+///   (import "env" "input" (func $input (param i64)))
+///   (import "env" "register_len" (func $register_len (param i64) (result i64)))
+///   (import "env" "read_register" (func $read_register (param i64 i64)))
+///   (import "env" "storage_remove" (func $storage_remove (param i64 i64 i64) (result i64)))
+///   (import "env" "memory" (memory 1))
+///   (func (export "clean") (local $pos i32) (local $end i32) (local $len i32)
+///     (call $input (i64.const 0))
+///     (local.set $end (i32.wrap_i64 (call $register_len (i64.const 0))))
+///     (call $read_register (i64.const 0) (i64.const 0))
+///     ;; Skip the length of the vector, each key being prefixed by its own length.
+///     (local.set $pos (i32.const 4))
+///     (block $done
+///       (loop $next
+///         (br_if $done (i32.ge_u (local.get $pos) (local.get $end)))
+///         (local.set $len (i32.load (local.get $pos)))
+///         (drop (call $storage_remove
+///           (i64.extend_i32_u (local.get $len))
+///           (i64.extend_i32_u (i32.add (local.get $pos) (i32.const 4)))
+///           (i64.const 1)))
+///         (local.set $pos (i32.add (i32.add (local.get $pos) (i32.const 4)) (local.get $len)))
+///         (br $next)))))
+/// ```
+#[rustfmt::skip]
+const STORAGE_CLEANER: &[u8] = &[
+    // Header.
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+    // Types.
+    0x01, 0x19, 0x05,
+    0x60, 0x01, 0x7e, 0x00,
+    0x60, 0x01, 0x7e, 0x01, 0x7e,
+    0x60, 0x02, 0x7e, 0x7e, 0x00,
+    0x60, 0x03, 0x7e, 0x7e, 0x7e, 0x01, 0x7e,
+    0x60, 0x00, 0x00,
+    // Imports.
+    0x02, 0x57, 0x05,
+    0x03, b'e', b'n', b'v', 0x05, b'i', b'n', b'p', b'u', b't', 0x00, 0x00,
+    0x03, b'e', b'n', b'v', 0x0c, b'r', b'e', b'g', b'i', b's', b't', b'e', b'r', b'_', b'l',
+    b'e', b'n', 0x00, 0x01,
+    0x03, b'e', b'n', b'v', 0x0d, b'r', b'e', b'a', b'd', b'_', b'r', b'e', b'g', b'i', b's',
+    b't', b'e', b'r', 0x00, 0x02,
+    0x03, b'e', b'n', b'v', 0x0e, b's', b't', b'o', b'r', b'a', b'g', b'e', b'_', b'r', b'e',
+    b'm', b'o', b'v', b'e', 0x00, 0x03,
+    0x03, b'e', b'n', b'v', 0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00, 0x01,
+    // Functions.
+    0x03, 0x02, 0x01, 0x04,
+    // Exports.
+    0x07, 0x09, 0x01, 0x05, b'c', b'l', b'e', b'a', b'n', 0x00, 0x04,
+    // Code.
+    0x0a, 0x49, 0x01, 0x47, 0x01, 0x03, 0x7f,
+    0x42, 0x00, 0x10, 0x00,
+    0x42, 0x00, 0x10, 0x01, 0xa7, 0x21, 0x01,
+    0x42, 0x00, 0x42, 0x00, 0x10, 0x02,
+    0x41, 0x04, 0x21, 0x00,
+    0x02, 0x40, 0x03, 0x40,
+    0x20, 0x00, 0x20, 0x01, 0x4f, 0x0d, 0x01,
+    0x20, 0x00, 0x28, 0x02, 0x00, 0x21, 0x02,
+    0x20, 0x02, 0xad,
+    0x20, 0x00, 0x41, 0x04, 0x6a, 0xad,
+    0x42, 0x01, 0x10, 0x03, 0x1a,
+    0x20, 0x00, 0x41, 0x04, 0x6a, 0x20, 0x02, 0x6a, 0x21, 0x00,
+    0x0c, 0x00, 0x0b, 0x0b, 0x0b,
+];
+
+/// Throwaway sandbox transactions against networks other than sandbox are dry-run in, along
+/// with the accounts the previous dry-run left in it.
+pub(crate) struct EstimationSandbox {
+    sandbox: Worker<Sandbox>,
+    dirty: BTreeSet<AccountId>,
+}
+
+/// Estimate the costs of sending `actions` from `signer` to `receiver_id`.
+///
+/// On sandbox, the transaction is executed in place, and every account its receipts touched
+/// is reverted afterwards. On any other network, the accounts the transaction touches are
+/// imported into a throwaway sandbox, where the transaction is executed instead. That
+/// sandbox is spawned on the first estimation, and reused by every later estimation of
+/// `worker` and its clones.
+pub(crate) async fn estimate(
+    worker: &Worker<dyn Network>,
+    signer: &InMemorySigner,
+    receiver_id: &AccountId,
+    actions: Vec<Action>,
+) -> Result<GasEstimate> {
+    if worker.workspace.is_sandbox() {
+        estimate_in_place(worker, signer, receiver_id, actions).await
+    } else {
+        estimate_in_sandbox(worker, signer, receiver_id, actions).await
+    }
+}
+
+async fn estimate_in_place(
+    worker: &Worker<dyn Network>,
+    signer: &InMemorySigner,
+    receiver_id: &AccountId,
+    actions: Vec<Action>,
+) -> Result<GasEstimate> {
+    let block_hash = *worker.view_block().await?.hash();
+    let outcome = send_batch_tx_and_retry(
+        worker.client(),
+        signer,
+        receiver_id,
+        actions,
+        DEFAULT_WAIT_UNTIL,
+    )
+    .await;
+
+    // Revert even if sending the transaction failed, since it might have been partially applied.
+    let touched = match &outcome {
+        Ok(outcome) => touched_accounts(outcome),
+        Err(_) => vec![signer.account_id.clone(), receiver_id.clone()]
+            .into_iter()
+            .collect(),
+    };
+    revert_accounts(worker, signer, &touched, block_hash).await?;
+
+    Ok(GasEstimate::new(
+        ExecutionFinalResult::from_view(outcome?),
+        None,
+    ))
+}
+
+/// Revert `account_ids` on the sandbox of `worker` to their state at `block_hash`.
+///
+/// State patches can only add or overwrite records, so the storage and access keys added
+/// since are removed first, before patching the accounts back out of their snapshots.
+/// Accounts which did not exist back then are deleted.
+async fn revert_accounts(
+    worker: &Worker<dyn Network>,
+    signer: &InMemorySigner,
+    account_ids: &BTreeSet<AccountId>,
+    block_hash: CryptoHash,
+) -> Result<()> {
+    let root_id = worker.workspace.info().root_id.clone();
+    let root_before = worker.view_account(&root_id).await?;
+    let current_block_hash = *worker.view_block().await?.hash();
+
+    let mut records = Vec::new();
+    let mut deleted_any = false;
+    for account_id in account_ids {
+        let snapshot =
+            AccountSnapshot::take_if_exists(worker, account_id, block_hash, true).await?;
+        let current =
+            AccountSnapshot::take_if_exists(worker, account_id, current_block_hash, true).await?;
+        match (snapshot, current) {
+            (Some(snapshot), Some(current)) => {
+                let cleanup = snapshot.cleanup_of(&current, signer);
+                if !cleanup.is_empty() {
+                    cleanup.run(worker.client(), signer, account_id).await?;
+                }
+                records.extend(snapshot.records(Some(&current.access_keys)));
+            }
+            (Some(snapshot), None) => records.extend(snapshot.records(None)),
+            (None, Some(current)) => {
+                Cleanup::deletion(&current, &root_id)
+                    .run(worker.client(), signer, account_id)
+                    .await?;
+                deleted_any = true;
+            }
+            (None, None) => {}
+        }
+    }
+
+    // The balance of deleted accounts went to the root account.
+    if deleted_any && !account_ids.contains(&root_id) {
+        records.push(StateRecord::Account {
+            account_id: root_id,
+            account: root_before.into_near_account(),
+        });
+    }
+    patch_records(worker.client(), records).await
+}
+
+async fn estimate_in_sandbox(
+    worker: &Worker<dyn Network>,
+    signer: &InMemorySigner,
+    receiver_id: &AccountId,
+    actions: Vec<Action>,
+) -> Result<GasEstimate> {
+    let block_hash = *worker.view_block().await?.hash();
+    let gas_price = worker.gas_price().block_hash(block_hash).await?;
+
+    let mut snapshots =
+        vec![AccountSnapshot::take(worker, &signer.account_id, block_hash, false).await?];
+    // Accounts found not to exist on the network, so that they are not looked up again.
+    let mut missing = BTreeSet::new();
+    if receiver_id != &signer.account_id {
+        match AccountSnapshot::take_if_exists(worker, receiver_id, block_hash, false).await? {
+            Some(snapshot) => snapshots.push(snapshot),
+            None => {
+                missing.insert(receiver_id.clone());
+            }
+        }
+    }
+
+    // Holding the lock for the whole dry-run keeps concurrent estimations from importing
+    // their accounts over each other.
+    let mut estimation_sandbox = worker.estimation_sandbox.lock().await;
+    let mut round = 1;
+    loop {
+        let sandbox = prepare_estimation_sandbox(&mut estimation_sandbox, signer).await?;
+        let records = snapshots
+            .iter()
+            .flat_map(|snapshot| snapshot.records(None))
+            .collect();
+        patch_records(sandbox.sandbox.client(), records).await?;
+        sandbox
+            .dirty
+            .extend(snapshots.iter().map(|snapshot| snapshot.account_id.clone()));
+
+        let outcome = send_batch_tx_and_retry(
+            sandbox.sandbox.client(),
+            signer,
+            receiver_id,
+            actions.clone(),
+            DEFAULT_WAIT_UNTIL,
+        )
+        .await?;
+        let touched = touched_accounts(&outcome);
+        sandbox.dirty.extend(touched.iter().cloned());
+
+        // Receipts reaching accounts which were not imported fail, so those which exist on the
+        // network get imported before dry-running the transaction again.
+        let mut imported_more = false;
+        if round < MAX_IMPORT_ROUNDS {
+            for account_id in touched {
+                if missing.contains(&account_id)
+                    || snapshots
+                        .iter()
+                        .any(|snapshot| snapshot.account_id == account_id)
+                {
+                    continue;
+                }
+                match AccountSnapshot::take_if_exists(worker, &account_id, block_hash, false)
+                    .await?
+                {
+                    Some(snapshot) => {
+                        snapshots.push(snapshot);
+                        imported_more = true;
+                    }
+                    None => {
+                        missing.insert(account_id);
+                    }
+                }
+            }
+        }
+
+        if !imported_more {
+            return Ok(GasEstimate::new(
+                ExecutionFinalResult::from_view(outcome),
+                Some(gas_price),
+            ));
+        }
+        round += 1;
+    }
+}
+
+/// Get the estimation sandbox ready for a new dry-run, by deleting the accounts left in it
+/// by the previous one, or spawning it if there is none or it cannot be cleaned up.
+async fn prepare_estimation_sandbox<'a>(
+    estimation_sandbox: &'a mut Option<EstimationSandbox>,
+    signer: &InMemorySigner,
+) -> Result<&'a mut EstimationSandbox> {
+    let reusable = match estimation_sandbox.as_mut() {
+        Some(sandbox) => sandbox.clear(signer).await.is_ok(),
+        None => false,
+    };
+    if !reusable {
+        *estimation_sandbox = Some(EstimationSandbox {
+            sandbox: crate::sandbox().await?,
+            dirty: BTreeSet::new(),
+        });
+    }
+    Ok(estimation_sandbox
+        .as_mut()
+        .expect("estimation sandbox was just spawned"))
+}
+
+impl EstimationSandbox {
+    /// Delete the accounts left in the sandbox by the previous dry-run, which is the only way
+    /// to get rid of the storage and access keys it added to them.
+    async fn clear(&mut self, signer: &InMemorySigner) -> Result<()> {
+        let root_id = self.sandbox.root_account()?.id().clone();
+        let block_hash = *self.sandbox.view_block().await?.hash();
+        for account_id in std::mem::take(&mut self.dirty) {
+            if account_id == root_id {
+                // Cannot be deleted, so the sandbox gets replaced instead.
+                return Err(crate::error::ErrorKind::Other
+                    .message("the estimation sandbox root account was modified"));
+            }
+            let current = AccountSnapshot::take_if_exists(
+                &self.sandbox.clone().coerce(),
+                &account_id,
+                block_hash,
+                true,
+            )
+            .await?;
+            if let Some(current) = current {
+                Cleanup::deletion(&current, &root_id)
+                    .run(self.sandbox.client(), signer, &account_id)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every account the transaction or one of its receipts was executed on.
+fn touched_accounts(outcome: &FinalExecutionOutcomeWithReceiptView) -> BTreeSet<AccountId> {
+    let outcome = &outcome.final_outcome;
+    std::iter::once(&outcome.transaction_outcome)
+        .chain(&outcome.receipts_outcome)
+        .map(|outcome| outcome.outcome.executor_id.clone())
+        .collect()
+}
+
+/// Storage and access keys to remove from an account, before optionally deleting it.
+struct Cleanup {
+    data_keys: Vec<Vec<u8>>,
+    access_keys: Vec<PublicKey>,
+    beneficiary_id: Option<AccountId>,
+}
+
+impl Cleanup {
+    /// Delete the account `current` was taken of, sending its balance to `beneficiary_id`.
+    fn deletion(current: &AccountSnapshot, beneficiary_id: &AccountId) -> Self {
+        Self {
+            data_keys: current.state.keys().cloned().collect(),
+            access_keys: Vec::new(),
+            beneficiary_id: Some(beneficiary_id.clone()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data_keys.is_empty() && self.access_keys.is_empty() && self.beneficiary_id.is_none()
+    }
+
+    /// Run the cleanup on `account_id`. Its keys are not necessarily known, so the key of
+    /// `signer` is granted full access to sign the cleanup with.
+    ///
+    /// Accounts with too much storage cannot be deleted, and contract code counts towards it,
+    /// so storage is removed by [`STORAGE_CLEANER`], which replaces the code of the account.
+    async fn run(
+        self,
+        client: &Client,
+        signer: &InMemorySigner,
+        account_id: &AccountId,
+    ) -> Result<()> {
+        let cleaner =
+            InMemorySigner::from_secret_key(account_id.clone(), signer.secret_key.clone());
+        patch_records(
+            client,
+            vec![StateRecord::AccessKey {
+                account_id: account_id.clone(),
+                public_key: cleaner.secret_key.public_key().into(),
+                access_key: AccessKey::full_access().into(),
+            }],
+        )
+        .await?;
+
+        let mut actions = Vec::new();
+        if !self.data_keys.is_empty() {
+            actions.push(
+                DeployContractAction {
+                    code: STORAGE_CLEANER.to_vec(),
+                }
+                .into(),
+            );
+            actions.push(
+                FunctionCallAction {
+                    method_name: "clean".into(),
+                    args: borsh::to_vec(&self.data_keys)
+                        .map_err(|e| crate::error::ErrorKind::DataConversion.custom(e))?,
+                    gas: CLEANUP_GAS.as_gas(),
+                    deposit: 0,
+                }
+                .into(),
+            );
+        }
+        actions.extend(self.access_keys.into_iter().map(|public_key| {
+            DeleteKeyAction {
+                public_key: public_key.0,
+            }
+            .into()
+        }));
+        if let Some(beneficiary_id) = self.beneficiary_id {
+            actions.push(DeleteAccountAction { beneficiary_id }.into());
+        }
+
+        let outcome =
+            send_batch_tx_and_retry(client, &cleaner, account_id, actions, DEFAULT_WAIT_UNTIL)
+                .await?;
+        ExecutionFinalResult::from_view(outcome).into_result()?;
+        Ok(())
+    }
+}
+
+/// Everything about an account that is required to recreate it through a state patch.
+struct AccountSnapshot {
+    account_id: AccountId,
+    details: AccountDetails,
+    code: Option<Vec<u8>>,
+    state: HashMap<Vec<u8>, Vec<u8>>,
+    access_keys: Vec<AccessKeyInfo>,
+}
+
+impl AccountSnapshot {
+    async fn take(
+        worker: &Worker<dyn Network>,
+        account_id: &AccountId,
+        block_hash: CryptoHash,
+        require_state: bool,
+    ) -> Result<Self> {
+        let details = worker
+            .view_account(account_id)
+            .block_hash(block_hash)
+            .await?;
+
+        let code = if details.code_hash != CryptoHash::default() {
+            Some(worker.view_code(account_id).block_hash(block_hash).await?)
+        } else {
+            None
+        };
+
+        // Networks like testnet and mainnet limit how much state can be viewed through RPC,
+        // so the state of large contracts can only be imported on a best effort basis.
+        let state = match worker.view_state(account_id).block_hash(block_hash).await {
+            Ok(state) => state,
+            Err(err) if !require_state => {
+                tracing::warn!(
+                    target: "workspaces",
+                    "could not import state of {} for estimation: {}",
+                    account_id,
+                    err
+                );
+                HashMap::new()
+            }
+            Err(err) => return Err(err),
+        };
+
+        let access_keys = worker
+            .view_access_keys(account_id)
+            .block_hash(block_hash)
+            .await?;
+
+        Ok(Self {
+            account_id: account_id.clone(),
+            details,
+            code,
+            state,
+            access_keys,
+        })
+    }
+
+    /// Like [`AccountSnapshot::take`], but `None` if the account does not exist at
+    /// `block_hash`.
+    async fn take_if_exists(
+        worker: &Worker<dyn Network>,
+        account_id: &AccountId,
+        block_hash: CryptoHash,
+        require_state: bool,
+    ) -> Result<Option<Self>> {
+        match Self::take(worker, account_id, block_hash, require_state).await {
+            Ok(snapshot) => Ok(Some(snapshot)),
+            Err(err) if err.is_unknown_account() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The storage and access keys `current` holds but this snapshot does not, which state
+    /// patches cannot remove. That includes the key of `signer` the cleanup is signed with.
+    fn cleanup_of(&self, current: &Self, signer: &InMemorySigner) -> Cleanup {
+        let data_keys: Vec<_> = current
+            .state
+            .keys()
+            .filter(|key| !self.state.contains_key(*key))
+            .cloned()
+            .collect();
+
+        let mut access_keys: Vec<_> = current
+            .access_keys
+            .iter()
+            .map(|info| info.public_key.clone())
+            .filter(|public_key| !self.has_access_key(public_key))
+            .collect();
+        let signer_key = signer.secret_key.public_key();
+        let needs_cleanup = !data_keys.is_empty() || !access_keys.is_empty();
+        if needs_cleanup && !self.has_access_key(&signer_key) && !access_keys.contains(&signer_key)
+        {
+            access_keys.push(signer_key);
+        }
+
+        Cleanup {
+            data_keys,
+            access_keys,
+            beneficiary_id: None,
+        }
+    }
+
+    fn has_access_key(&self, public_key: &PublicKey) -> bool {
+        self.access_keys
+            .iter()
+            .any(|info| &info.public_key == public_key)
+    }
+
+    /// Convert the snapshot into state records. When reverting an account, `current_keys`
+    /// holds its access keys, which keep whichever nonce is the highest so that transactions
+    /// cannot be replayed. Otherwise nonces are reset, since the nonces of networks other than
+    /// sandbox are derived from block heights far beyond those of the sandbox.
+    fn records(&self, current_keys: Option<&[AccessKeyInfo]>) -> Vec<StateRecord> {
+        let account_id = &self.account_id;
+        let mut records = vec![StateRecord::Account {
+            account_id: account_id.clone(),
+            account: self.details.clone().into_near_account(),
+        }];
+
+        if let Some(code) = &self.code {
+            records.push(StateRecord::Contract {
+                account_id: account_id.clone(),
+                code: code.clone(),
+            });
+        }
+
+        records.extend(self.state.iter().map(|(key, value)| StateRecord::Data {
+            account_id: account_id.clone(),
+            data_key: key.clone().into(),
+            value: value.clone().into(),
+        }));
+
+        records.extend(self.access_keys.iter().map(|info| {
+            let mut access_key = info.access_key.clone();
+            access_key.nonce = match current_keys {
+                Some(current_keys) => current_keys
+                    .iter()
+                    .find(|current| current.public_key == info.public_key)
+                    .map_or(access_key.nonce, |current| {
+                        access_key.nonce.max(current.access_key.nonce)
+                    }),
+                None => 0,
+            };
+            StateRecord::AccessKey {
+                account_id: account_id.clone(),
+                public_key: info.public_key.clone().into(),
+                access_key: access_key.into(),
+            }
+        }));
+
+        records
+    }
+}
@@ -1,4 +1,5 @@
 pub(crate) mod client;
+pub(crate) mod estimate;
 pub(crate) mod tool;
 
 pub mod patch;
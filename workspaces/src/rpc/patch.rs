@@ -5,6 +5,7 @@ use near_token::NearToken;
 
 use crate::error::SandboxErrorCode;
 use crate::network::{Sandbox, DEV_ACCOUNT_SEED};
+use crate::rpc::client::Client;
 use crate::types::account::AccountDetails;
 use crate::types::{BlockHeight, KeyType, PublicKey, SecretKey};
 use crate::{AccessKey, AccountDetailsPatch, Result};
@@ -324,19 +325,22 @@ impl PatchTransaction {
             self.records
         };
 
-        self.worker
-            .client()
-            .query(&RpcSandboxPatchStateRequest {
-                records: records.clone(),
-            })
-            .await
-            .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
-
-        self.worker
-            .client()
-            .query(&RpcSandboxPatchStateRequest { records })
-            .await
-            .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
-        Ok(())
+        patch_records(self.worker.client(), records).await
     }
 }
+
+/// Send the state records to be patched into the sandbox network that `client` points to.
+pub(crate) async fn patch_records(client: &Client, records: Vec<StateRecord>) -> Result<()> {
+    client
+        .query(&RpcSandboxPatchStateRequest {
+            records: records.clone(),
+        })
+        .await
+        .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
+
+    client
+        .query(&RpcSandboxPatchStateRequest { records })
+        .await
+        .map_err(|err| SandboxErrorCode::PatchStateFailure.custom(err))?;
+    Ok(())
+}
@@ -34,6 +34,7 @@
 
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
+use std::sync::Arc;
 
 use near_account_id::AccountId;
 use near_jsonrpc_client::methods::query::RpcQueryResponse;
@@ -50,7 +51,9 @@ use crate::result::ViewResultDetails;
 use crate::rpc::client::Client;
 use crate::rpc::{tool, BoxFuture};
 use crate::types::account::AccountDetails;
-use crate::types::{AccessKey, AccessKeyInfo, BlockHeight, Finality, PublicKey, ShardId};
+use crate::types::{
+    AccessKey, AccessKeyInfo, BlockHeight, ContractAbi, Finality, PublicKey, ShardId,
+};
 use crate::{Block, Chunk, CryptoHash, Result};
 
 /// `Query` object allows creating queries into the network of our choice. This object is
@@ -159,6 +162,8 @@ pub trait ProcessQuery {
 pub struct ViewFunction {
     pub(crate) account_id: AccountId,
     pub(crate) function: Function,
+    /// ABI of the contract to check the view against before sending it.
+    pub(crate) abi: Option<Arc<ContractAbi>>,
 }
 
 pub struct ViewCode {
@@ -192,6 +197,10 @@ impl ProcessQuery for ViewFunction {
     type Output = ViewResultDetails;
 
     fn into_request(self, block_reference: BlockReference) -> Result<Self::Method> {
+        if let Some(abi) = &self.abi {
+            abi.check_view(&self.account_id, &self.function)?;
+        }
+
         Ok(Self::Method {
             block_reference,
             request: QueryRequest::CallFunction {
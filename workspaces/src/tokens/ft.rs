@@ -0,0 +1,240 @@
+//! Helpers for [NEP-141](https://nomicon.io/Standards/Tokens/FungibleToken/Core) fungible
+//! tokens and their [NEP-145](https://nomicon.io/Standards/StorageManagement) storage
+//! management.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{Amount, StorageBalance, StorageBalanceBounds, ONE_YOCTO};
+use crate::error::ErrorKind;
+use crate::result::{ExecutionFinalResult, Result};
+use crate::types::AccountId;
+use crate::{Account, Contract};
+
+/// Metadata of a fungible token, as defined by
+/// [NEP-148](https://nomicon.io/Standards/Tokens/FungibleToken/Metadata).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FungibleTokenMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+    pub decimals: u8,
+}
+
+/// A NEP-141 fungible token contract, wrapping a [`Contract`] with typed methods. Amounts
+/// are plain `u128`s, sent to and read from the contract as decimal strings.
+///
+/// Calls that move tokens are signed by the account given to them, with the 1 yoctoNEAR
+/// deposit the standard requires attached. Before transferring, the receiver is checked to
+/// be registered with the token through NEP-145 storage management, so that a missing
+/// registration is reported upfront instead of as a contract panic.
+///
+/// ```ignore
+/// let ft = FungibleToken::new(contract);
+/// ft.storage_deposit(&alice, None).await?.into_result()?;
+/// ft.ft_transfer(&owner, alice.id(), 100, None).await?.into_result()?;
+/// assert_eq!(ft.ft_balance_of(alice.id()).await?, 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FungibleToken {
+    contract: Contract,
+}
+
+impl FungibleToken {
+    /// Wrap the fungible token `contract`.
+    pub fn new(contract: Contract) -> Self {
+        Self { contract }
+    }
+
+    /// Grab the id of the token contract.
+    pub fn id(&self) -> &AccountId {
+        self.contract.id()
+    }
+
+    /// Grab the underlying contract, to call into methods not covered by this wrapper.
+    pub fn as_contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// Balance of `account_id`.
+    pub async fn ft_balance_of(&self, account_id: &AccountId) -> Result<u128> {
+        let balance: Amount = self
+            .contract
+            .view("ft_balance_of")
+            .args_json(json!({ "account_id": account_id }))
+            .await?
+            .json()?;
+        Ok(balance.0)
+    }
+
+    /// Total supply of the token.
+    pub async fn ft_total_supply(&self) -> Result<u128> {
+        let supply: Amount = self.contract.view("ft_total_supply").await?.json()?;
+        Ok(supply.0)
+    }
+
+    /// Metadata of the token.
+    pub async fn ft_metadata(&self) -> Result<FungibleTokenMetadata> {
+        self.contract.view("ft_metadata").await?.json()
+    }
+
+    /// Transfer `amount` tokens from `sender` to `receiver_id`.
+    pub async fn ft_transfer(
+        &self,
+        sender: &Account,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<&str>,
+    ) -> Result<ExecutionFinalResult> {
+        self.check_registered(receiver_id).await?;
+        sender
+            .call(self.id(), "ft_transfer")
+            .args_json(json!({
+                "receiver_id": receiver_id,
+                "amount": amount.to_string(),
+                "memo": memo,
+            }))
+            .deposit(ONE_YOCTO)
+            .transact()
+            .await
+    }
+
+    /// Transfer `amount` tokens from `sender` to the `receiver_id` contract, and call its
+    /// `ft_on_transfer` method with `msg`. The receiver may refund part of the tokens,
+    /// which is reported by the returned [`FtTransferCallResult`].
+    pub async fn ft_transfer_call(
+        &self,
+        sender: &Account,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<&str>,
+        msg: &str,
+    ) -> Result<FtTransferCallResult> {
+        self.check_registered(receiver_id).await?;
+        let details = sender
+            .call(self.id(), "ft_transfer_call")
+            .args_json(json!({
+                "receiver_id": receiver_id,
+                "amount": amount.to_string(),
+                "memo": memo,
+                "msg": msg,
+            }))
+            .deposit(ONE_YOCTO)
+            .max_gas()
+            .transact()
+            .await?;
+
+        Ok(FtTransferCallResult { details, amount })
+    }
+
+    /// Storage balance of `account_id`, or `None` if it is not registered with the token.
+    pub async fn storage_balance_of(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Option<StorageBalance>> {
+        self.contract
+            .view("storage_balance_of")
+            .args_json(json!({ "account_id": account_id }))
+            .await?
+            .json()
+    }
+
+    /// Minimum and maximum storage balance of an account registered with the token.
+    pub async fn storage_balance_bounds(&self) -> Result<StorageBalanceBounds> {
+        self.contract.view("storage_balance_bounds").await?.json()
+    }
+
+    /// Whether `account_id` is registered with the token, and can hold tokens.
+    pub async fn is_registered(&self, account_id: &AccountId) -> Result<bool> {
+        Ok(self.storage_balance_of(account_id).await?.is_some())
+    }
+
+    /// Register `account_id`, or `payer` itself if `None`, with the token. The minimum
+    /// storage balance is paid by `payer`, and any excess is refunded.
+    pub async fn storage_deposit(
+        &self,
+        payer: &Account,
+        account_id: Option<&AccountId>,
+    ) -> Result<ExecutionFinalResult> {
+        let bounds = self.storage_balance_bounds().await?;
+        payer
+            .call(self.id(), "storage_deposit")
+            .args_json(json!({
+                "account_id": account_id,
+                "registration_only": true,
+            }))
+            .deposit(bounds.min)
+            .transact()
+            .await
+    }
+
+    /// Register `account_id` with the token through [`FungibleToken::storage_deposit`],
+    /// unless it is registered already. Returns the execution of the registration, if one
+    /// was needed.
+    pub async fn ensure_registered(
+        &self,
+        payer: &Account,
+        account_id: &AccountId,
+    ) -> Result<Option<ExecutionFinalResult>> {
+        if self.is_registered(account_id).await? {
+            return Ok(None);
+        }
+        self.storage_deposit(payer, Some(account_id))
+            .await
+            .map(Some)
+    }
+
+    /// Fail if `account_id` is not registered with the token. Tokens which do not implement
+    /// storage management do not require any registration, so they are let through.
+    async fn check_registered(&self, account_id: &AccountId) -> Result<()> {
+        match self.storage_balance_of(account_id).await {
+            Ok(Some(_)) => Ok(()),
+            Err(err) if err.is_method_not_found() => Ok(()),
+            Err(err) => Err(err),
+            Ok(None) => Err(ErrorKind::Execution.message(format!(
+                "{account_id} is not registered with the token {}, register it first through \
+                 `storage_deposit`",
+                self.id()
+            ))),
+        }
+    }
+}
+
+impl From<Contract> for FungibleToken {
+    fn from(contract: Contract) -> Self {
+        Self::new(contract)
+    }
+}
+
+/// Outcome of [`FungibleToken::ft_transfer_call`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct FtTransferCallResult {
+    /// Execution details of the transfer, including the call into the receiver and the
+    /// resolution of the transfer.
+    pub details: ExecutionFinalResult,
+    /// Amount of tokens sent along with the call.
+    pub amount: u128,
+}
+
+impl FtTransferCallResult {
+    /// Whether the transfer, and its resolution, succeeded.
+    pub fn is_success(&self) -> bool {
+        self.details.is_success()
+    }
+
+    /// Amount of tokens the receiver kept, as returned by `ft_resolve_transfer`.
+    pub fn used_amount(&self) -> Result<u128> {
+        let used: Amount = self.details.clone().json()?;
+        Ok(used.0)
+    }
+
+    /// Amount of tokens refunded to the sender by `ft_resolve_transfer`, which is the part
+    /// of the amount the receiver did not keep.
+    pub fn refunded_amount(&self) -> Result<u128> {
+        Ok(self.amount.saturating_sub(self.used_amount()?))
+    }
+}
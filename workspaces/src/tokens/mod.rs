@@ -0,0 +1,44 @@
+//! Typed wrappers over contracts implementing the NEAR token standards, so that tests do
+//! not need to repeat the JSON shapes and deposits these standards require.
+
+mod ft;
+mod nft;
+
+pub use self::ft::{FtTransferCallResult, FungibleToken, FungibleTokenMetadata};
+pub use self::nft::{
+    NftContractMetadata, NftExecution, NftTransferCallResult, NonFungibleToken, Token,
+    TokenMetadata,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::result::dec_format;
+use crate::types::NearToken;
+
+/// Deposit the token standards require on calls that move tokens, which ensures they are
+/// signed by a full access key.
+pub(crate) const ONE_YOCTO: NearToken = NearToken::from_yoctonear(1);
+
+/// Storage balance of an account registered with a contract, as defined by
+/// [NEP-145](https://nomicon.io/Standards/StorageManagement).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageBalance {
+    /// Total amount deposited for the storage of the account.
+    pub total: NearToken,
+    /// Part of the deposit not used for storage, which can be withdrawn.
+    pub available: NearToken,
+}
+
+/// Bounds of the storage balance of an account registered with a contract, as defined by
+/// [NEP-145](https://nomicon.io/Standards/StorageManagement).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageBalanceBounds {
+    /// Minimum deposit required to register an account.
+    pub min: NearToken,
+    /// Maximum storage balance of an account, if any.
+    pub max: Option<NearToken>,
+}
+
+/// A `U128` amount, which token standards represent as a decimal string in JSON.
+#[derive(Deserialize)]
+pub(crate) struct Amount(#[serde(with = "dec_format")] pub(crate) u128);
@@ -0,0 +1,395 @@
+//! Helpers for [NEP-171](https://nomicon.io/Standards/Tokens/NonFungibleToken/Core)
+//! non-fungible tokens, along with their
+//! [NEP-177](https://nomicon.io/Standards/Tokens/NonFungibleToken/Metadata) metadata,
+//! [NEP-178](https://nomicon.io/Standards/Tokens/NonFungibleToken/ApprovalManagement)
+//! approvals and
+//! [NEP-181](https://nomicon.io/Standards/Tokens/NonFungibleToken/Enumeration) enumeration.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{Amount, ONE_YOCTO};
+use crate::result::{ExecutionFinalResult, Nep171Event, NftMint, NftTransfer, Result};
+use crate::types::{AccountId, NearToken};
+use crate::{Account, Contract};
+
+/// Deposit attached to calls that store data on behalf of the caller, such as minting a
+/// token or approving an account. Token contracts refund whatever is not used for storage.
+const STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(100);
+
+/// A non-fungible token, as returned by `nft_token` and the enumeration methods.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    pub token_id: String,
+    pub owner_id: AccountId,
+    /// Metadata of the token, if the contract implements NEP-177.
+    pub metadata: Option<TokenMetadata>,
+    /// Accounts approved to transfer the token along with their approval id, if the
+    /// contract implements NEP-178.
+    pub approved_account_ids: Option<HashMap<AccountId, u64>>,
+}
+
+/// Metadata of a single token, as defined by NEP-177.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<String>,
+    pub copies: Option<u64>,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub starts_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub extra: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+/// Metadata of a non-fungible token contract, as defined by NEP-177.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftContractMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub base_uri: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+/// A NEP-171 non-fungible token contract, wrapping a [`Contract`] with typed methods.
+///
+/// Calls are signed by the account given to them, with the 1 yoctoNEAR deposit the
+/// standard requires attached where needed. Calls that store data, such as minting or
+/// approving, attach 0.1 NEAR for storage, of which the contract refunds what it does not
+/// use. The `nft_mint` and `nft_transfer` events emitted by calls are available through the
+/// returned [`NftExecution`].
+///
+/// ```ignore
+/// let nft = NonFungibleToken::new(contract);
+/// let minted = nft.nft_mint(&owner, "0", owner.id(), &TokenMetadata::default()).await?;
+/// assert_eq!(minted.mints()?[0].token_ids, ["0"]);
+/// nft.nft_transfer(&owner, alice.id(), "0", None, None).await?.into_result()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct NonFungibleToken {
+    contract: Contract,
+}
+
+impl NonFungibleToken {
+    /// Wrap the non-fungible token `contract`.
+    pub fn new(contract: Contract) -> Self {
+        Self { contract }
+    }
+
+    /// Grab the id of the token contract.
+    pub fn id(&self) -> &AccountId {
+        self.contract.id()
+    }
+
+    /// Grab the underlying contract, to call into methods not covered by this wrapper.
+    pub fn as_contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// Metadata of the contract.
+    pub async fn nft_metadata(&self) -> Result<NftContractMetadata> {
+        self.contract.view("nft_metadata").await?.json()
+    }
+
+    /// The token `token_id`, or `None` if it does not exist.
+    pub async fn nft_token(&self, token_id: &str) -> Result<Option<Token>> {
+        self.contract
+            .view("nft_token")
+            .args_json(json!({ "token_id": token_id }))
+            .await?
+            .json()
+    }
+
+    /// Mint the token `token_id` to `owner_id`, through the `nft_mint` method of the
+    /// contract. Minting is not part of the standard, so this follows the signature used
+    /// by the near-sdk example contracts.
+    pub async fn nft_mint(
+        &self,
+        minter: &Account,
+        token_id: &str,
+        owner_id: &AccountId,
+        metadata: &TokenMetadata,
+    ) -> Result<NftExecution> {
+        minter
+            .call(self.id(), "nft_mint")
+            .args_json(json!({
+                "token_id": token_id,
+                "token_owner_id": owner_id,
+                "token_metadata": metadata,
+            }))
+            .deposit(STORAGE_DEPOSIT)
+            .transact()
+            .await
+            .map(NftExecution::new)
+    }
+
+    /// Transfer the token `token_id` from `sender` to `receiver_id`. The sender is either
+    /// the owner, or an account approved with `approval_id`.
+    pub async fn nft_transfer(
+        &self,
+        sender: &Account,
+        receiver_id: &AccountId,
+        token_id: &str,
+        approval_id: Option<u64>,
+        memo: Option<&str>,
+    ) -> Result<NftExecution> {
+        sender
+            .call(self.id(), "nft_transfer")
+            .args_json(json!({
+                "receiver_id": receiver_id,
+                "token_id": token_id,
+                "approval_id": approval_id,
+                "memo": memo,
+            }))
+            .deposit(ONE_YOCTO)
+            .transact()
+            .await
+            .map(NftExecution::new)
+    }
+
+    /// Transfer the token `token_id` from `sender` to the `receiver_id` contract, and call
+    /// its `nft_on_transfer` method with `msg`. The receiver may return the token, which is
+    /// reported by the returned [`NftTransferCallResult`].
+    pub async fn nft_transfer_call(
+        &self,
+        sender: &Account,
+        receiver_id: &AccountId,
+        token_id: &str,
+        approval_id: Option<u64>,
+        memo: Option<&str>,
+        msg: &str,
+    ) -> Result<NftTransferCallResult> {
+        sender
+            .call(self.id(), "nft_transfer_call")
+            .args_json(json!({
+                "receiver_id": receiver_id,
+                "token_id": token_id,
+                "approval_id": approval_id,
+                "memo": memo,
+                "msg": msg,
+            }))
+            .deposit(ONE_YOCTO)
+            .max_gas()
+            .transact()
+            .await
+            .map(|details| NftTransferCallResult {
+                execution: NftExecution::new(details),
+            })
+    }
+
+    /// Approve `account_id` to transfer the token `token_id` of `owner`. If `msg` is given,
+    /// the `nft_on_approve` method of `account_id` gets called with it.
+    pub async fn nft_approve(
+        &self,
+        owner: &Account,
+        token_id: &str,
+        account_id: &AccountId,
+        msg: Option<&str>,
+    ) -> Result<ExecutionFinalResult> {
+        let call = owner
+            .call(self.id(), "nft_approve")
+            .args_json(json!({
+                "token_id": token_id,
+                "account_id": account_id,
+                "msg": msg,
+            }))
+            .deposit(STORAGE_DEPOSIT);
+        let call = if msg.is_some() { call.max_gas() } else { call };
+        call.transact().await
+    }
+
+    /// Whether `approved_account_id` is approved to transfer the token `token_id`, with
+    /// `approval_id` if given.
+    pub async fn nft_is_approved(
+        &self,
+        token_id: &str,
+        approved_account_id: &AccountId,
+        approval_id: Option<u64>,
+    ) -> Result<bool> {
+        self.contract
+            .view("nft_is_approved")
+            .args_json(json!({
+                "token_id": token_id,
+                "approved_account_id": approved_account_id,
+                "approval_id": approval_id,
+            }))
+            .await?
+            .json()
+    }
+
+    /// Revoke the approval of `account_id` to transfer the token `token_id` of `owner`.
+    pub async fn nft_revoke(
+        &self,
+        owner: &Account,
+        token_id: &str,
+        account_id: &AccountId,
+    ) -> Result<ExecutionFinalResult> {
+        owner
+            .call(self.id(), "nft_revoke")
+            .args_json(json!({ "token_id": token_id, "account_id": account_id }))
+            .deposit(ONE_YOCTO)
+            .transact()
+            .await
+    }
+
+    /// Revoke the approvals of every account to transfer the token `token_id` of `owner`.
+    pub async fn nft_revoke_all(
+        &self,
+        owner: &Account,
+        token_id: &str,
+    ) -> Result<ExecutionFinalResult> {
+        owner
+            .call(self.id(), "nft_revoke_all")
+            .args_json(json!({ "token_id": token_id }))
+            .deposit(ONE_YOCTO)
+            .transact()
+            .await
+    }
+
+    /// Number of tokens in the contract.
+    pub async fn nft_total_supply(&self) -> Result<u128> {
+        let supply: Amount = self.contract.view("nft_total_supply").await?.json()?;
+        Ok(supply.0)
+    }
+
+    /// Tokens of the contract, starting at the index `from_index` and up to `limit` tokens.
+    pub async fn nft_tokens(
+        &self,
+        from_index: Option<u128>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Token>> {
+        self.contract
+            .view("nft_tokens")
+            .args_json(json!({
+                "from_index": from_index.map(|index| index.to_string()),
+                "limit": limit,
+            }))
+            .await?
+            .json()
+    }
+
+    /// Number of tokens owned by `account_id`.
+    pub async fn nft_supply_for_owner(&self, account_id: &AccountId) -> Result<u128> {
+        let supply: Amount = self
+            .contract
+            .view("nft_supply_for_owner")
+            .args_json(json!({ "account_id": account_id }))
+            .await?
+            .json()?;
+        Ok(supply.0)
+    }
+
+    /// Tokens owned by `account_id`, starting at the index `from_index` and up to `limit`
+    /// tokens.
+    pub async fn nft_tokens_for_owner(
+        &self,
+        account_id: &AccountId,
+        from_index: Option<u128>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Token>> {
+        self.contract
+            .view("nft_tokens_for_owner")
+            .args_json(json!({
+                "account_id": account_id,
+                "from_index": from_index.map(|index| index.to_string()),
+                "limit": limit,
+            }))
+            .await?
+            .json()
+    }
+}
+
+impl From<Contract> for NonFungibleToken {
+    fn from(contract: Contract) -> Self {
+        Self::new(contract)
+    }
+}
+
+/// Execution of a call into a non-fungible token contract, along with the NEP-171 events
+/// it emitted.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct NftExecution {
+    /// Execution details of the call.
+    pub details: ExecutionFinalResult,
+}
+
+impl NftExecution {
+    fn new(details: ExecutionFinalResult) -> Self {
+        Self { details }
+    }
+
+    /// Whether the call succeeded.
+    pub fn is_success(&self) -> bool {
+        self.details.is_success()
+    }
+
+    /// Check whether the call succeeded, like [`ExecutionFinalResult::into_result`].
+    pub fn into_result(self) -> Result<Self> {
+        if let Err(failure) = self.details.clone().into_result() {
+            return Err(failure.into());
+        }
+        Ok(self)
+    }
+
+    /// Data of the `nft_mint` events emitted by the call.
+    pub fn mints(&self) -> Result<Vec<NftMint>> {
+        Ok(self
+            .events()?
+            .into_iter()
+            .filter_map(|event| match event {
+                Nep171Event::NftMint(mints) => Some(mints),
+                _ => None,
+            })
+            .flatten()
+            .collect())
+    }
+
+    /// Data of the `nft_transfer` events emitted by the call.
+    pub fn transfers(&self) -> Result<Vec<NftTransfer>> {
+        Ok(self
+            .events()?
+            .into_iter()
+            .filter_map(|event| match event {
+                Nep171Event::NftTransfer(transfers) => Some(transfers),
+                _ => None,
+            })
+            .flatten()
+            .collect())
+    }
+
+    fn events(&self) -> Result<Vec<Nep171Event>> {
+        self.details.typed_events::<Nep171Event>()
+    }
+}
+
+/// Outcome of [`NonFungibleToken::nft_transfer_call`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct NftTransferCallResult {
+    /// Execution of the transfer, including the call into the receiver and the resolution
+    /// of the transfer.
+    pub execution: NftExecution,
+}
+
+impl NftTransferCallResult {
+    /// Whether the transfer, and its resolution, succeeded.
+    pub fn is_success(&self) -> bool {
+        self.execution.is_success()
+    }
+
+    /// Whether the receiver kept the token, as returned by `nft_resolve_transfer`. `false`
+    /// means the token got returned to its previous owner.
+    pub fn is_transferred(&self) -> Result<bool> {
+        self.execution.details.clone().json()
+    }
+}
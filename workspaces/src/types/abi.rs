@@ -0,0 +1,280 @@
+use std::fmt;
+use std::path::Path;
+
+use near_abi::{AbiFunction, AbiFunctionKind, AbiFunctionModifier, AbiParameters, AbiRoot};
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+
+use crate::error::ErrorKind;
+use crate::operations::Function;
+use crate::result::Result;
+use crate::types::{AccountId, NearToken};
+
+/// Name of the view function near-sdk embeds into contracts built with ABI support, which
+/// returns the zstd compressed JSON ABI of the contract.
+pub(crate) const CONTRACT_ABI_METHOD: &str = "__contract_abi";
+
+/// ABI of a contract, as generated by near-sdk and cargo-near. Once attached to a
+/// [`Contract`] through [`Contract::with_abi`], calls and views made through the contract
+/// are checked against it before anything is sent to the network.
+///
+/// [`Contract`]: crate::Contract
+/// [`Contract::with_abi`]: crate::Contract::with_abi
+#[derive(Clone, Debug)]
+pub struct ContractAbi {
+    root: AbiRoot,
+}
+
+impl ContractAbi {
+    /// Parse the ABI from its JSON bytes, or from its zstd compressed JSON bytes as returned
+    /// by the `__contract_abi` view function and stored by cargo-near with `--compress-abi`.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        let json = if bytes.starts_with(&ZSTD_MAGIC) {
+            zstd::decode_all(bytes)
+                .map_err(|e| ErrorKind::Abi.full("failed to decompress the contract ABI", e))?
+        } else {
+            bytes.to_vec()
+        };
+
+        let root = serde_json::from_slice(&json)
+            .map_err(|e| ErrorKind::Abi.full("failed to parse the contract ABI", e))?;
+        Ok(Self { root })
+    }
+
+    /// Read the ABI out of a compiled artifact such as `target/near/<name>_abi.json`, or its
+    /// compressed `.zst` counterpart.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| {
+            ErrorKind::Io.full(format!("failed to read ABI file {}", path.display()), e)
+        })?;
+        Self::from_slice(&bytes)
+    }
+
+    /// The raw ABI, for inspecting anything not covered by this type.
+    pub fn root(&self) -> &AbiRoot {
+        &self.root
+    }
+
+    /// All functions exposed by the contract.
+    pub fn functions(&self) -> &[AbiFunction] {
+        &self.root.body.functions
+    }
+
+    /// Grab the function named `name`, if the contract exposes it.
+    pub fn function(&self, name: &str) -> Option<&AbiFunction> {
+        self.functions()
+            .iter()
+            .find(|function| function.name == name)
+    }
+
+    /// Check a function call transaction signed by `signer_id` against the ABI.
+    pub(crate) fn check_call(
+        &self,
+        contract_id: &AccountId,
+        signer_id: &AccountId,
+        function: &Function,
+    ) -> Result<()> {
+        let abi_fn = self.lookup(contract_id, &function.name)?;
+        let payable = abi_fn
+            .modifiers
+            .iter()
+            .any(|modifier| matches!(modifier, AbiFunctionModifier::Payable));
+        let private = abi_fn
+            .modifiers
+            .iter()
+            .any(|modifier| matches!(modifier, AbiFunctionModifier::Private));
+
+        if function.deposit > NearToken::from_yoctonear(0) && !payable {
+            return Err(ErrorKind::Abi.message(format!(
+                "`{}` on {contract_id} is not payable, but a deposit of {} was attached",
+                function.name, function.deposit
+            )));
+        }
+        if private && signer_id != contract_id {
+            return Err(ErrorKind::Abi.message(format!(
+                "`{}` on {contract_id} is private and can only be called by the contract itself, \
+                 not by {signer_id}",
+                function.name
+            )));
+        }
+
+        self.check_args(contract_id, abi_fn, function)
+    }
+
+    /// Check a view call against the ABI.
+    pub(crate) fn check_view(&self, contract_id: &AccountId, function: &Function) -> Result<()> {
+        let abi_fn = self.lookup(contract_id, &function.name)?;
+        if !matches!(abi_fn.kind, AbiFunctionKind::View) {
+            return Err(ErrorKind::Abi.message(format!(
+                "`{}` on {contract_id} is a change method and cannot be viewed, call it instead",
+                function.name
+            )));
+        }
+
+        self.check_args(contract_id, abi_fn, function)
+    }
+
+    fn lookup(&self, contract_id: &AccountId, name: &str) -> Result<&AbiFunction> {
+        self.function(name).ok_or_else(|| {
+            ErrorKind::Abi.message(format!(
+                "`{name}` is not a function of {contract_id}, available functions are: {}",
+                self.functions()
+                    .iter()
+                    .map(|function| function.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        })
+    }
+
+    /// Check that JSON args hold every required parameter and nothing else, with the
+    /// expected JSON types. Borsh args cannot be checked without their schema being
+    /// interpreted, so they are let through.
+    fn check_args(
+        &self,
+        contract_id: &AccountId,
+        abi_fn: &AbiFunction,
+        function: &Function,
+    ) -> Result<()> {
+        let params = match &abi_fn.params {
+            AbiParameters::Json { args } => args,
+            AbiParameters::Borsh { .. } => return Ok(()),
+        };
+        let args = match &function.args {
+            Ok(args) => args,
+            // The serialization error surfaces once the function gets sent.
+            Err(_) => return Ok(()),
+        };
+
+        let invalid = |reason: String| {
+            ErrorKind::Abi.message(format!(
+                "invalid args for `{}` on {contract_id}: {reason}",
+                function.name
+            ))
+        };
+
+        let args = if args.is_empty() {
+            serde_json::Map::new()
+        } else {
+            match serde_json::from_slice(args) {
+                Ok(Value::Object(args)) => args,
+                Ok(other) => return Err(invalid(format!("expected a JSON object, got {other}"))),
+                Err(e) => return Err(invalid(format!("args are not valid JSON: {e}"))),
+            }
+        };
+
+        if let Some(unknown) = args
+            .keys()
+            .find(|key| !params.iter().any(|param| &param.name == *key))
+        {
+            return Err(invalid(format!(
+                "unknown argument `{unknown}`, expected: {}",
+                params
+                    .iter()
+                    .map(|param| param.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        for param in params {
+            match args.get(&param.name) {
+                None if !self.accepts_null(&param.type_schema) => {
+                    return Err(invalid(format!("missing argument `{}`", param.name)));
+                }
+                Some(value) if !self.accepts(&param.type_schema, value) => {
+                    return Err(invalid(format!(
+                        "argument `{}` has the wrong type, got {value}",
+                        param.name
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether `value` has one of the JSON types allowed by `schema`. This only checks the
+    /// outermost type, anything more precise is left to the contract.
+    fn accepts(&self, schema: &Schema, value: &Value) -> bool {
+        let schema = match self.resolve(schema) {
+            Some(schema) => schema,
+            None => return true,
+        };
+        if let Some(subschemas) = &schema.subschemas {
+            if let Some(all_of) = &subschemas.all_of {
+                return all_of.iter().all(|schema| self.accepts(schema, value));
+            }
+            if let Some(any_of) = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref()) {
+                return any_of.iter().any(|schema| self.accepts(schema, value));
+            }
+        }
+
+        match &schema.instance_type {
+            None => true,
+            Some(SingleOrVec::Single(ty)) => matches_type(ty, value),
+            Some(SingleOrVec::Vec(types)) => types.iter().any(|ty| matches_type(ty, value)),
+        }
+    }
+
+    /// Whether `schema` allows null, meaning that the argument can be left out.
+    fn accepts_null(&self, schema: &Schema) -> bool {
+        self.accepts(schema, &Value::Null)
+    }
+
+    /// Follow `$ref`s into the definitions of the ABI. `None` means anything is accepted.
+    fn resolve<'a>(&'a self, schema: &'a Schema) -> Option<&'a SchemaObject> {
+        let mut schema = match schema {
+            Schema::Bool(_) => return None,
+            Schema::Object(object) => object,
+        };
+        while let Some(reference) = &schema.reference {
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+            schema = match self.root.body.root_schema.definitions.get(name) {
+                Some(Schema::Object(object)) => object,
+                _ => return None,
+            };
+        }
+        Some(schema)
+    }
+}
+
+impl fmt::Display for ContractAbi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.root.metadata.name.as_deref().unwrap_or("<unnamed>"),
+            self.root.metadata.version.as_deref().unwrap_or("")
+        )?;
+        for function in self.functions() {
+            let kind = match function.kind {
+                AbiFunctionKind::View => "view",
+                AbiFunctionKind::Call => "call",
+            };
+            write!(f, "\n  {kind} {}", function.name)?;
+            if let AbiParameters::Json { args } = &function.params {
+                let args = args.iter().map(|arg| arg.name.as_str()).collect::<Vec<_>>();
+                write!(f, "({})", args.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Magic number every zstd frame starts with.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+fn matches_type(ty: &InstanceType, value: &Value) -> bool {
+    match ty {
+        InstanceType::Null => value.is_null(),
+        InstanceType::Boolean => value.is_boolean(),
+        InstanceType::Object => value.is_object(),
+        InstanceType::Array => value.is_array(),
+        InstanceType::Number => value.is_number(),
+        InstanceType::String => value.is_string(),
+        InstanceType::Integer => value.is_i64() || value.is_u64(),
+    }
+}
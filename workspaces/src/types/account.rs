@@ -1,5 +1,6 @@
 use std::fmt;
 use std::path::Path;
+use std::sync::Arc;
 
 use near_primitives::types::StorageUsage;
 use near_primitives::version::PROTOCOL_VERSION;
@@ -9,10 +10,16 @@ use crate::error::ErrorKind;
 use crate::rpc::query::{
     Query, ViewAccessKey, ViewAccessKeyList, ViewAccount, ViewCode, ViewFunction, ViewState,
 };
-use crate::types::{AccountId, InMemorySigner, NearToken, PublicKey, SecretKey};
+use crate::types::abi::CONTRACT_ABI_METHOD;
+use crate::types::{
+    AccessKey, AccessKeyPermission, AccountId, ContractAbi, ContractCode, InMemorySigner, KeyType,
+    NearToken, PublicKey, SecretKey,
+};
 use crate::{BlockHeight, CryptoHash, Network, Worker};
 
-use crate::operations::{CallTransaction, CreateAccountTransaction, Transaction};
+use crate::operations::{
+    CallTransaction, CreateAccountTransaction, Transaction, UpgradeTransaction,
+};
 use crate::result::{Execution, ExecutionFinalResult, Result};
 
 /// `Account` is directly associated to an account in the network provided by the
@@ -24,6 +31,9 @@ use crate::result::{Execution, ExecutionFinalResult, Result};
 pub struct Account {
     signer: InMemorySigner,
     worker: Worker<dyn Network>,
+    /// Whether calls report the allowance they consume, for accounts signing with one of
+    /// their other access keys.
+    track_allowance: bool,
 }
 
 impl fmt::Debug for Account {
@@ -50,14 +60,18 @@ impl Account {
         sk: SecretKey,
         worker: &Worker<impl Network + 'static>,
     ) -> Self {
-        Self {
-            signer: InMemorySigner::from_secret_key(id, sk),
-            worker: worker.clone().coerce(),
-        }
+        Self::new(
+            InMemorySigner::from_secret_key(id, sk),
+            worker.clone().coerce(),
+        )
     }
 
     pub(crate) fn new(signer: InMemorySigner, worker: Worker<dyn Network>) -> Self {
-        Self { signer, worker }
+        Self {
+            signer,
+            worker,
+            track_allowance: false,
+        }
     }
 
     /// Grab the current account identifier
@@ -76,12 +90,17 @@ impl Account {
     /// rest of the call details. Note that the current [`Account`]'s secret
     /// key is used as the signer of the transaction.
     pub fn call(&self, contract_id: &AccountId, function: &str) -> CallTransaction {
-        CallTransaction::new(
+        let call = CallTransaction::new(
             self.worker.clone(),
             contract_id.to_owned(),
             self.signer.clone(),
             function,
-        )
+        );
+        if self.track_allowance {
+            call.track_allowance()
+        } else {
+            call
+        }
     }
 
     /// View call to a specified contract function. Returns a result which can
@@ -154,6 +173,45 @@ impl Account {
         )
     }
 
+    /// Create the NEAR-implicit account derived from `sk` by transferring `amount` to it
+    /// from this account. See [`SecretKey::implicit_account_id`] for how the id is derived.
+    /// This works on any network, as implicit accounts are created by the first transfer
+    /// they receive.
+    ///
+    /// The account gets the public key of `sk` added as a full access key, so the returned
+    /// [`Account`] can sign transactions right away. ETH-implicit accounts, derived from
+    /// SECP256K1 keys, are rejected: they are controlled through the wallet contract the
+    /// protocol deploys to them instead of an access key, so there would be nothing for the
+    /// returned [`Account`] to sign with. Transfer to [`SecretKey::implicit_account_id`] to
+    /// create one of those.
+    pub async fn create_implicit_account(
+        &self,
+        sk: SecretKey,
+        amount: NearToken,
+    ) -> Result<Execution<Account>> {
+        if matches!(sk.key_type(), KeyType::SECP256K1) {
+            return Err(ErrorKind::Usage.message(
+                "ETH-implicit accounts cannot sign transactions with their key; transfer to \
+                 `SecretKey::implicit_account_id` to create one instead",
+            ));
+        }
+
+        let id = sk.implicit_account_id();
+        let details = self.transfer_near(&id, amount).await?;
+        for callback in self.worker.tx_callbacks.iter() {
+            callback(&details)?;
+        }
+
+        let account = Account::new(InMemorySigner::from_secret_key(id, sk), self.worker.clone());
+        if details.is_success() {
+            self.worker.track_account(account.signer())?;
+        }
+        Ok(Execution {
+            result: account,
+            details,
+        })
+    }
+
     /// Deploy contract code or WASM bytes to the account, and return us a new
     /// [`Contract`] object that we can use to interact with the contract.
     pub async fn deploy(&self, wasm: &[u8]) -> Result<Execution<Contract>> {
@@ -169,6 +227,14 @@ impl Account {
         })
     }
 
+    /// Deploy contract code to this account, like [`Account::deploy`], but only once it
+    /// passes [`ContractCode::validate`]. Invalid code fails with [`ErrorKind::Wasm`]
+    /// without anything being sent.
+    pub async fn deploy_checked(&self, wasm: &[u8]) -> Result<Execution<Contract>> {
+        ContractCode::parse(wasm)?.validate(self.worker.max_contract_size().await?)?;
+        self.deploy(wasm).await
+    }
+
     /// Start a batch transaction, using the current account as the signer and
     /// making calls into the contract provided by `contract_id`. Returns a
     /// [`Transaction`] object that we can use to add Actions to the batched
@@ -200,6 +266,117 @@ impl Account {
     pub fn set_secret_key(&mut self, sk: SecretKey) {
         self.signer.secret_key = sk;
     }
+
+    /// Create another [`Account`] object for the same account, but one that signs all of
+    /// its transactions with the provided [`SecretKey`]. This is useful for acting on
+    /// behalf of this account with one of its other access keys, such as a function call
+    /// access key added through [`Transaction::add_key`].
+    ///
+    /// Like [`CallTransaction::sign_with`], calls made through the returned account report
+    /// the allowance they consume through [`ExecutionFinalResult::allowance_used`].
+    pub fn with_key(&self, sk: SecretKey) -> Self {
+        self.signing_with(sk, true)
+    }
+
+    fn signing_with(&self, sk: SecretKey, track_allowance: bool) -> Self {
+        Self {
+            signer: InMemorySigner::from_secret_key(self.id().clone(), sk),
+            worker: self.worker.clone(),
+            track_allowance,
+        }
+    }
+
+    /// Add `pk` as a full access key of this account.
+    pub async fn add_full_access_key(&self, pk: &PublicKey) -> Result<ExecutionFinalResult> {
+        self.add_key(pk, AccessKey::full_access()).await
+    }
+
+    /// Add `pk` as a function call access key of this account, which is only permitted to
+    /// call `method_names` on `receiver_id`. An empty list of method names permits calling
+    /// any method. The key can spend up to `allowance` on gas, or without limit if `None`.
+    pub async fn add_function_call_key(
+        &self,
+        pk: &PublicKey,
+        receiver_id: &AccountId,
+        method_names: &[&str],
+        allowance: Option<NearToken>,
+    ) -> Result<ExecutionFinalResult> {
+        let ak = AccessKey::function_call_access(receiver_id, method_names, allowance);
+        self.add_key(pk, ak).await
+    }
+
+    async fn add_key(&self, pk: &PublicKey, ak: AccessKey) -> Result<ExecutionFinalResult> {
+        let outcome = self
+            .batch(self.id())
+            .add_key(pk.clone(), ak)
+            .transact()
+            .await?;
+        if outcome.is_success() {
+            self.worker
+                .client()
+                .seed_access_key_nonce(self.id(), &pk.0)
+                .await?;
+        }
+        Ok(outcome)
+    }
+
+    /// Delete the access key `pk` from this account.
+    pub async fn delete_key(&self, pk: &PublicKey) -> Result<ExecutionFinalResult> {
+        let outcome = self
+            .batch(self.id())
+            .delete_key(pk.clone())
+            .transact()
+            .await?;
+        if outcome.is_success() {
+            self.worker
+                .client()
+                .forget_access_key_nonce(self.id(), &pk.0)
+                .await;
+        }
+        Ok(outcome)
+    }
+
+    /// Generate a new ED25519 key and add it to this account with the permissions of `ak`.
+    /// Returns another [`Account`] object for the same account that signs with the new key,
+    /// which reports the allowance its calls consume if the key is a function call access key.
+    /// Fails with the execution failure if the key could not be added.
+    pub async fn generate_and_add_key(&self, ak: AccessKey) -> Result<Execution<Account>> {
+        let sk = SecretKey::from_random(KeyType::ED25519);
+        let track_allowance = matches!(ak.permission, AccessKeyPermission::FunctionCall(_));
+        let details = self.add_key(&sk.public_key(), ak).await?;
+        details.clone().into_result()?;
+        Ok(Execution {
+            result: self.signing_with(sk, track_allowance),
+            details,
+        })
+    }
+
+    /// Replace the key this account signs with by a newly generated full access key. The new
+    /// key is added and the current one deleted within a single transaction. Returns another
+    /// [`Account`] object for the same account that signs with the new key. Fails with the
+    /// execution failure if the keys could not be swapped, in which case the current key
+    /// is kept.
+    pub async fn rotate_key(&self) -> Result<Execution<Account>> {
+        let sk = SecretKey::from_random(KeyType::ED25519);
+        let old_pk = self.secret_key().public_key();
+        let details = self
+            .batch(self.id())
+            .add_key(sk.public_key(), AccessKey::full_access())
+            .delete_key(old_pk.clone())
+            .transact()
+            .await?;
+        details.clone().into_result()?;
+
+        let client = self.worker.client();
+        client.forget_access_key_nonce(self.id(), &old_pk.0).await;
+        client
+            .seed_access_key_nonce(self.id(), &sk.public_key().0)
+            .await?;
+        Ok(Execution {
+            result: self.signing_with(sk, false),
+            details,
+        })
+    }
 }
 
 /// `Contract` is directly associated to a contract in the network provided by the
@@ -210,6 +387,7 @@ impl Account {
 #[derive(Clone)]
 pub struct Contract {
     pub(crate) account: Account,
+    abi: Option<Arc<ContractAbi>>,
 }
 
 impl fmt::Debug for Contract {
@@ -231,13 +409,11 @@ impl Contract {
     }
 
     pub(crate) fn new(signer: InMemorySigner, worker: Worker<dyn Network>) -> Self {
-        Self {
-            account: Account::new(signer, worker),
-        }
+        Self::account(Account::new(signer, worker))
     }
 
     pub(crate) fn account(account: Account) -> Self {
-        Self { account }
+        Self { account, abi: None }
     }
 
     /// Grab the current contract's account identifier
@@ -272,13 +448,50 @@ impl Contract {
     /// If we want to make use of the contract's secret key as a signer to call
     /// into another contract, use `contract.as_account().call` instead.
     pub fn call(&self, function: &str) -> CallTransaction {
-        self.account.call(self.id(), function)
+        self.account.call(self.id(), function).abi(self.abi.clone())
     }
 
     /// Call a view function into the current contract. Returns a result which can
     /// be deserialized into borsh or JSON.
     pub fn view(&self, function: &str) -> Query<'_, ViewFunction> {
-        self.account.view(self.id(), function)
+        let mut query = self.account.view(self.id(), function);
+        query.method.abi = self.abi.clone();
+        query
+    }
+
+    /// Fetch the ABI embedded into this contract through its `__contract_abi` view function,
+    /// which near-sdk generates for contracts built by cargo-near with the ABI embedded.
+    /// To load the ABI out of a compiled artifact instead, use [`ContractAbi::from_file`].
+    pub async fn abi(&self) -> Result<ContractAbi> {
+        let result = self
+            .account
+            .view(self.id(), CONTRACT_ABI_METHOD)
+            .await
+            .map_err(|e| {
+                ErrorKind::Abi.full(
+                    format!("failed to fetch the ABI embedded into {}", self.id()),
+                    e,
+                )
+            })?;
+        ContractAbi::from_slice(&result.result)
+    }
+
+    /// Check every call and view made through this contract against `abi` before sending
+    /// it. Calls to unknown functions, with args that do not match the parameters, with a
+    /// deposit to a function that is not payable, or to a private function from another
+    /// account, fail with [`ErrorKind::Abi`] without anything being sent.
+    ///
+    /// ```ignore
+    /// let contract = contract.clone().with_abi(contract.abi().await?);
+    /// ```
+    pub fn with_abi(mut self, abi: ContractAbi) -> Self {
+        self.abi = Some(Arc::new(abi));
+        self
+    }
+
+    /// The ABI attached to this contract through [`Contract::with_abi`], if any.
+    pub fn attached_abi(&self) -> Option<&ContractAbi> {
+        self.abi.as_deref()
     }
 
     /// View the WASM code bytes of this contract.
@@ -286,7 +499,8 @@ impl Contract {
         self.account.worker.view_code(self.id())
     }
 
-    /// View a contract's state map of key value pairs.
+    /// View a contract's state map of key value pairs. Values can be decoded through
+    /// [`ContractStateExt`](crate::types::ContractStateExt).
     pub fn view_state(&self) -> Query<'_, ViewState> {
         self.account.worker.view_state(self.id())
     }
@@ -315,6 +529,23 @@ impl Contract {
         self.account.delete_account(beneficiary_id).await
     }
 
+    /// Upgrade this contract to `wasm`. Returns an [`UpgradeTransaction`] to optionally
+    /// add a migration method call to, which gets sent along with the deploy in a single
+    /// batch transaction:
+    ///
+    /// ```ignore
+    /// let diff = contract
+    ///     .upgrade(&v2_wasm)
+    ///     .migrate("migrate", json!({}))
+    ///     .transact()
+    ///     .await?
+    ///     .into_result()?;
+    /// assert!(diff.changed().contains_key(b"STATE".as_slice()));
+    /// ```
+    pub fn upgrade(&self, wasm: &[u8]) -> UpgradeTransaction {
+        UpgradeTransaction::new(self.clone(), wasm)
+    }
+
     /// Start a batch transaction, using the current contract's secret key as the
     /// signer, making calls into itself. Returns a [`Transaction`] object that
     /// we can use to add Actions to the batched transaction. Call `transact`
@@ -393,7 +624,7 @@ impl From<AccountDetails> for AccountDetailsPatch {
 
 /// Details of an Account or Contract. This is an non-exhaustive list of items
 /// that the account stores in the blockchain state.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub struct AccountDetails {
     pub balance: NearToken,
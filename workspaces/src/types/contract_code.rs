@@ -0,0 +1,383 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use wasmparser::{ExternalKind, Parser, Payload, TypeRef};
+
+use crate::error::ErrorKind;
+use crate::result::Result;
+use crate::CryptoHash;
+
+/// Module contracts import host functions and memory from.
+const HOST_MODULE: &str = "env";
+
+/// Functions the NEAR runtime provides to contracts through the `env` module. Newer protocol
+/// versions keep adding to these, which can be accepted through
+/// [`ContractCode::with_host_functions`] until they are listed here.
+const HOST_FUNCTIONS: &[&str] = &[
+    // Registers
+    "read_register",
+    "register_len",
+    "write_register",
+    // Context
+    "current_account_id",
+    "signer_account_id",
+    "signer_account_pk",
+    "predecessor_account_id",
+    "input",
+    "block_index",
+    "block_timestamp",
+    "epoch_height",
+    "storage_usage",
+    // Economics
+    "account_balance",
+    "account_locked_balance",
+    "attached_deposit",
+    "prepaid_gas",
+    "used_gas",
+    // Math
+    "random_seed",
+    "sha256",
+    "keccak256",
+    "keccak512",
+    "ripemd160",
+    "ecrecover",
+    "ed25519_verify",
+    "alt_bn128_g1_multiexp",
+    "alt_bn128_g1_sum",
+    "alt_bn128_pairing_check",
+    "bls12381_p1_sum",
+    "bls12381_p2_sum",
+    "bls12381_g1_multiexp",
+    "bls12381_g2_multiexp",
+    "bls12381_map_fp_to_g1",
+    "bls12381_map_fp2_to_g2",
+    "bls12381_pairing_check",
+    "bls12381_p1_decompress",
+    "bls12381_p2_decompress",
+    // Miscellaneous
+    "value_return",
+    "panic",
+    "panic_utf8",
+    "log_utf8",
+    "log_utf16",
+    "abort",
+    // Promises
+    "promise_create",
+    "promise_then",
+    "promise_and",
+    "promise_batch_create",
+    "promise_batch_then",
+    "promise_batch_action_create_account",
+    "promise_batch_action_deploy_contract",
+    "promise_batch_action_deploy_global_contract",
+    "promise_batch_action_deploy_global_contract_by_account_id",
+    "promise_batch_action_use_global_contract",
+    "promise_batch_action_use_global_contract_by_account_id",
+    "promise_batch_action_function_call",
+    "promise_batch_action_function_call_weight",
+    "promise_batch_action_transfer",
+    "promise_batch_action_stake",
+    "promise_batch_action_add_key_with_full_access",
+    "promise_batch_action_add_key_with_function_call",
+    "promise_batch_action_delete_key",
+    "promise_batch_action_delete_account",
+    "promise_yield_create",
+    "promise_yield_resume",
+    "promise_results_count",
+    "promise_result",
+    "promise_return",
+    // Storage
+    "storage_write",
+    "storage_read",
+    "storage_remove",
+    "storage_has_key",
+    "storage_iter_prefix",
+    "storage_iter_range",
+    "storage_iter_next",
+    // Validators
+    "validator_stake",
+    "validator_total_stake",
+    // Gas metering, injected by older toolchains
+    "gas",
+    // Only available on sandbox nodes
+    "sandbox_debug_log",
+];
+
+/// An item a contract imports from the runtime, such as a host function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ContractImport {
+    /// Module the item is imported from, which is `env` for the NEAR runtime.
+    pub module: String,
+    /// Name of the imported item.
+    pub name: String,
+    /// Whether the item is a function, as opposed to memory, a table or a global.
+    pub is_function: bool,
+}
+
+impl ContractImport {
+    /// Whether the NEAR runtime provides this import. Host functions added by protocol
+    /// versions newer than this library are not known to it, see
+    /// [`ContractCode::with_host_functions`].
+    pub fn is_host_provided(&self) -> bool {
+        self.is_from_host_module()
+            && if self.is_function {
+                HOST_FUNCTIONS.contains(&self.name.as_str())
+            } else {
+                self.name == "memory"
+            }
+    }
+
+    /// Whether this is imported from the module the NEAR runtime provides, whether or not
+    /// the runtime provides this item.
+    pub fn is_from_host_module(&self) -> bool {
+        self.module == HOST_MODULE
+    }
+}
+
+impl fmt::Display for ContractImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.module, self.name)
+    }
+}
+
+/// Contract code parsed locally, to inspect and validate it before deploying it. Deploying
+/// arbitrary bytes only fails once the contract gets called, with an obscure compilation
+/// error, while [`ContractCode::validate`] tells what is wrong upfront.
+///
+/// The code can be compared with the bytes returned by [`Worker::view_code`], or with the
+/// `code_hash` of an account, to check what is deployed on chain:
+///
+/// ```ignore
+/// let code = ContractCode::parse(wasm)?;
+/// assert_eq!(code, contract.view_code().await?);
+/// assert_eq!(code.code_hash(), contract.view_account().await?.code_hash);
+/// ```
+///
+/// [`Worker::view_code`]: crate::Worker::view_code
+#[derive(Clone)]
+pub struct ContractCode {
+    wasm: Vec<u8>,
+    code_hash: CryptoHash,
+    exports: Vec<String>,
+    imports: Vec<ContractImport>,
+    extra_host_functions: Vec<String>,
+}
+
+impl ContractCode {
+    /// Parse the given wasm bytes. This fails if they are not a well formed wasm module,
+    /// but does not check them against the rules of the runtime, for that see
+    /// [`ContractCode::validate`].
+    pub fn parse(wasm: impl Into<Vec<u8>>) -> Result<Self> {
+        let wasm = wasm.into();
+        let mut exports = Vec::new();
+        let mut imports = Vec::new();
+
+        for payload in Parser::new(0).parse_all(&wasm) {
+            let invalid = |e| ErrorKind::Wasm.full("contract code is not a valid wasm module", e);
+            match payload.map_err(invalid)? {
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.map_err(invalid)?;
+                        if export.kind == ExternalKind::Func {
+                            exports.push(export.name.to_string());
+                        }
+                    }
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.map_err(invalid)?;
+                        imports.push(ContractImport {
+                            module: import.module.to_string(),
+                            name: import.name.to_string(),
+                            is_function: matches!(import.ty, TypeRef::Func(_)),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            code_hash: CryptoHash::hash_bytes(&wasm),
+            wasm,
+            exports,
+            imports,
+            extra_host_functions: Vec::new(),
+        })
+    }
+
+    /// The raw wasm bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.wasm
+    }
+
+    /// Consume this and grab the raw wasm bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.wasm
+    }
+
+    /// Size of the code in bytes, which is limited by the `max_contract_size` of the
+    /// protocol, see [`Worker::max_contract_size`].
+    ///
+    /// [`Worker::max_contract_size`]: crate::Worker::max_contract_size
+    pub fn size(&self) -> usize {
+        self.wasm.len()
+    }
+
+    /// Hash of the code, the same as the `code_hash` of an account it is deployed to.
+    pub fn code_hash(&self) -> CryptoHash {
+        self.code_hash
+    }
+
+    /// Names of the exported functions, which are the methods that can be called on the
+    /// contract.
+    pub fn exports(&self) -> &[String] {
+        &self.exports
+    }
+
+    /// Whether the contract exports a function named `name`.
+    pub fn has_export(&self, name: &str) -> bool {
+        self.exports.iter().any(|export| export == name)
+    }
+
+    /// Everything the contract imports from the runtime.
+    pub fn imports(&self) -> &[ContractImport] {
+        &self.imports
+    }
+
+    /// Accept imports of the host functions `names` from the `env` module on top of the
+    /// ones known to this library, such as those added by newer protocol versions.
+    pub fn with_host_functions<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extra_host_functions
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Imports the NEAR runtime does not provide, which make the contract fail to compile.
+    pub fn unknown_imports(&self) -> Vec<&ContractImport> {
+        self.imports
+            .iter()
+            .filter(|import| {
+                !import.is_host_provided()
+                    && !(import.is_from_host_module()
+                        && import.is_function
+                        && self.extra_host_functions.contains(&import.name))
+            })
+            .collect()
+    }
+
+    /// Check the code against the rules of the runtime: its size must be within
+    /// `max_contract_size`, it must only import what the runtime provides, and it must
+    /// export at least one function. Errors are of kind [`ErrorKind::Wasm`].
+    ///
+    /// The size limit of the network a worker is connected to is given by
+    /// [`Worker::max_contract_size`].
+    ///
+    /// [`Worker::max_contract_size`]: crate::Worker::max_contract_size
+    pub fn validate(&self, max_contract_size: u64) -> Result<()> {
+        if self.size() as u64 > max_contract_size {
+            return Err(ErrorKind::Wasm.message(format!(
+                "contract code is {} bytes, over the limit of {max_contract_size} bytes",
+                self.size()
+            )));
+        }
+
+        // Unknown host functions may just be newer than this library, while imports from
+        // other modules can never be provided, so they are told apart.
+        let (unknown_host, other_modules): (Vec<_>, Vec<_>) = self
+            .unknown_imports()
+            .into_iter()
+            .partition(|import| import.is_from_host_module());
+        let mut problems = Vec::new();
+        if !unknown_host.is_empty() {
+            problems.push(format!(
+                "imports items the runtime does not provide from `{HOST_MODULE}`: {} \
+                 (see `ContractCode::with_host_functions` for host functions of newer \
+                 protocol versions)",
+                join(&unknown_host)
+            ));
+        }
+        if !other_modules.is_empty() {
+            problems.push(format!(
+                "imports items from modules other than `{HOST_MODULE}`: {}",
+                join(&other_modules)
+            ));
+        }
+        if !problems.is_empty() {
+            return Err(
+                ErrorKind::Wasm.message(format!("contract code {}", problems.join(", and ")))
+            );
+        }
+
+        if self.exports.is_empty() {
+            return Err(ErrorKind::Wasm.message("contract code does not export any function"));
+        }
+
+        Ok(())
+    }
+}
+
+fn join(imports: &[&ContractImport]) -> String {
+    imports
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Debug for ContractCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContractCode")
+            .field("size", &self.size())
+            .field("code_hash", &self.code_hash)
+            .field("exports", &self.exports)
+            .finish()
+    }
+}
+
+impl PartialEq for ContractCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.code_hash == other.code_hash
+    }
+}
+
+impl Eq for ContractCode {}
+
+impl PartialEq<[u8]> for ContractCode {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.wasm == other
+    }
+}
+
+impl PartialEq<Vec<u8>> for ContractCode {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        &self.wasm == other
+    }
+}
+
+impl PartialEq<CryptoHash> for ContractCode {
+    fn eq(&self, other: &CryptoHash) -> bool {
+        &self.code_hash == other
+    }
+}
+
+impl TryFrom<Vec<u8>> for ContractCode {
+    type Error = crate::error::Error;
+
+    fn try_from(wasm: Vec<u8>) -> Result<Self> {
+        Self::parse(wasm)
+    }
+}
+
+impl TryFrom<&[u8]> for ContractCode {
+    type Error = crate::error::Error;
+
+    fn try_from(wasm: &[u8]) -> Result<Self> {
+        Self::parse(wasm)
+    }
+}
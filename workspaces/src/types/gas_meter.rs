@@ -1,8 +1,15 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
 use std::panic::{RefUnwindSafe, UnwindSafe};
 use std::sync::{Arc, Mutex};
 
-use super::Gas;
-use crate::result::Result;
+use near_account_id::AccountId;
+use near_primitives::views::ActionView;
+use serde::Serialize;
+
+use super::{Gas, NearToken};
+use crate::error::ErrorKind;
+use crate::result::{ExecutionFinalResult, Result};
 use crate::Worker;
 
 /// A hook that is called on every transaction that is sent to the network.
@@ -12,7 +19,8 @@ use crate::Worker;
 /// The auto-traits [`Send`], [`Sync`], [`UnwindSafe`] and [`RefUnwindSafe`] are added explicitly because they
 /// do not fall under the rules the compiler uses to automatically add them.
 /// See here: <https://doc.rust-lang.org/reference/special-types-and-traits.html#auto-traits>
-pub(crate) type GasHook = Arc<dyn Fn(Gas) -> Result<()> + Send + Sync + UnwindSafe + RefUnwindSafe>;
+pub(crate) type GasHook =
+    Arc<dyn Fn(&ExecutionFinalResult) -> Result<()> + Send + Sync + UnwindSafe + RefUnwindSafe>;
 
 /// Allows you to meter the amount of gas consumed by transaction(s).
 /// Note: This only works with transactions that resolve to [`crate::result::ExecutionFinalResult`]
@@ -33,41 +41,220 @@ pub(crate) type GasHook = Arc<dyn Fn(Gas) -> Result<()> + Send + Sync + UnwindSa
 ///    .await?;
 ///
 /// println!("Total Gas consumed: {}", meter.elapsed()?);
+/// println!("{}", meter.report()?.to_markdown());
 /// ```
 pub struct GasMeter {
-    gas: Arc<Mutex<Gas>>,
+    usage: Arc<Mutex<MeterState>>,
+    hook: GasHook,
+}
+
+/// Everything recorded by a [`GasMeter`] so far.
+#[derive(Default)]
+struct MeterState {
+    gas: u64,
+    tokens_burnt: u128,
+    transactions: u64,
+    calls: BTreeMap<(AccountId, Option<String>), CallUsage>,
+}
+
+#[derive(Default)]
+struct CallUsage {
+    transactions: u64,
+    gas: u64,
+    tokens_burnt: u128,
+}
+
+impl MeterState {
+    fn record(&mut self, result: &ExecutionFinalResult) {
+        let gas = result.total_gas_burnt.as_gas();
+        let tokens_burnt: u128 = result
+            .outcomes()
+            .iter()
+            .map(|outcome| outcome.tokens_burnt.as_yoctonear())
+            .sum();
+
+        self.gas += gas;
+        self.tokens_burnt += tokens_burnt;
+        self.transactions += 1;
+
+        let call = self.calls.entry(call_key(result)).or_default();
+        call.transactions += 1;
+        call.gas += gas;
+        call.tokens_burnt += tokens_burnt;
+    }
+}
+
+/// The receiver and method names a transaction is accounted under. Transactions calling
+/// multiple methods in a batch are accounted under all of their names joined by a `+`.
+fn call_key(result: &ExecutionFinalResult) -> (AccountId, Option<String>) {
+    let receiver_id = result.details.signed_transaction.as_ref().map_or_else(
+        || result.outcome().executor_id.clone(),
+        |tx| tx.receiver_id.clone(),
+    );
+    let methods: Vec<&str> = result
+        .details
+        .signed_transaction
+        .iter()
+        .flat_map(|tx| &tx.actions)
+        .filter_map(|action| match action {
+            ActionView::FunctionCall { method_name, .. } => Some(method_name.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let method = if methods.is_empty() {
+        None
+    } else {
+        Some(methods.join("+"))
+    };
+    (receiver_id, method)
 }
 
 impl GasMeter {
     /// Create a new gas meter with 0 gas consumed.
     pub fn now<T: ?Sized>(worker: &mut Worker<T>) -> Self {
-        let meter = Self {
-            gas: Arc::new(Mutex::new(Gas::from_gas(0))),
-        };
+        let usage = Arc::new(Mutex::new(MeterState::default()));
 
-        let gas_consumed = Arc::downgrade(&Arc::clone(&meter.gas));
-        worker.tx_callbacks.push(Arc::new(move |gas: Gas| {
+        let recorder = Arc::downgrade(&usage);
+        let hook: GasHook = Arc::new(move |result: &ExecutionFinalResult| {
             // upgrades if meter is still alive, else noop.
-            if let Some(consumed) = gas_consumed.upgrade() {
-                let mut consumed = consumed.lock()?;
-                *consumed = Gas::from_gas(consumed.as_gas() + gas.as_gas());
+            if let Some(usage) = recorder.upgrade() {
+                usage.lock()?.record(result);
             }
 
             Ok(())
-        }));
+        });
+        worker.tx_callbacks.push(Arc::clone(&hook));
 
-        meter
+        Self { usage, hook }
+    }
+
+    /// Stop metering the transactions sent through `worker`, removing this meter from its
+    /// hooks. Accounts and contracts grabbed from the worker before this call hold their
+    /// own copy of the hooks, so their transactions are still metered until this meter
+    /// is dropped.
+    pub fn deregister<T: ?Sized>(&self, worker: &mut Worker<T>) {
+        worker
+            .tx_callbacks
+            .retain(|hook| !Arc::ptr_eq(hook, &self.hook));
     }
 
     /// Get the total amount of gas consumed.
     pub fn elapsed(&self) -> Result<Gas> {
-        let meter = self.gas.lock()?;
-        Ok(*meter)
+        let usage = self.usage.lock()?;
+        Ok(Gas::from_gas(usage.gas))
+    }
+
+    /// Get the total amount of tokens burnt for the gas consumed.
+    pub fn tokens_burnt(&self) -> Result<NearToken> {
+        let usage = self.usage.lock()?;
+        Ok(NearToken::from_yoctonear(usage.tokens_burnt))
     }
 
-    /// Reset the gas consumed to 0.
+    /// Get the number of transactions metered.
+    pub fn transactions(&self) -> Result<u64> {
+        let usage = self.usage.lock()?;
+        Ok(usage.transactions)
+    }
+
+    /// Grab a report of the gas consumed so far, broken down per receiver and method.
+    pub fn report(&self) -> Result<GasReport> {
+        let usage = self.usage.lock()?;
+        let calls = usage
+            .calls
+            .iter()
+            .map(|((receiver_id, method), call)| CallGasUsage {
+                receiver_id: receiver_id.clone(),
+                method: method.clone(),
+                transactions: call.transactions,
+                gas: Gas::from_gas(call.gas),
+                tokens_burnt: NearToken::from_yoctonear(call.tokens_burnt),
+            })
+            .collect();
+
+        Ok(GasReport {
+            total_gas: Gas::from_gas(usage.gas),
+            tokens_burnt: NearToken::from_yoctonear(usage.tokens_burnt),
+            transactions: usage.transactions,
+            calls,
+        })
+    }
+
+    /// Reset the gas consumed to 0, clearing the breakdown as well.
     pub fn reset(&self) -> Result<()> {
-        *self.gas.lock()? = Gas::from_gas(0);
+        *self.usage.lock()? = MeterState::default();
         Ok(())
     }
 }
+
+/// Report of the gas consumed by the transactions metered by a [`GasMeter`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct GasReport {
+    /// Total gas burnt by all the transactions.
+    pub total_gas: Gas,
+    /// Total tokens burnt for the gas of all the transactions.
+    pub tokens_burnt: NearToken,
+    /// Number of transactions metered.
+    pub transactions: u64,
+    /// Gas consumed per receiver and method, ordered by receiver and method.
+    pub calls: Vec<CallGasUsage>,
+}
+
+/// Gas consumed by the transactions sent to a receiver calling a specific method.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct CallGasUsage {
+    /// The receiver of the transactions.
+    pub receiver_id: AccountId,
+    /// Name of the method called, or `None` for transactions without function calls such
+    /// as transfers or account creation. Batches calling multiple methods are reported
+    /// with the method names joined by a `+`.
+    pub method: Option<String>,
+    /// Number of transactions sent.
+    pub transactions: u64,
+    /// Gas burnt by the transactions and all of their receipts.
+    pub gas: Gas,
+    /// Tokens burnt for the gas of the transactions and all of their receipts.
+    pub tokens_burnt: NearToken,
+}
+
+impl GasReport {
+    /// Usage of a specific method on a receiver, if it was called. Pass `None` as the
+    /// method for transactions without function calls.
+    pub fn get(&self, receiver_id: &AccountId, method: Option<&str>) -> Option<&CallGasUsage> {
+        self.calls
+            .iter()
+            .find(|call| &call.receiver_id == receiver_id && call.method.as_deref() == method)
+    }
+
+    /// Export the report as pretty printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ErrorKind::DataConversion.custom(e))
+    }
+
+    /// Export the report as a Markdown table, with a row per receiver and method.
+    pub fn to_markdown(&self) -> String {
+        let mut table = String::from(
+            "| Receiver | Method | Transactions | Gas | Tokens burnt |\n\
+             |----------|--------|-------------:|----:|-------------:|\n",
+        );
+        for call in &self.calls {
+            let _ = writeln!(
+                table,
+                "| {} | {} | {} | {} | {} |",
+                call.receiver_id,
+                call.method.as_deref().unwrap_or("-"),
+                call.transactions,
+                call.gas,
+                call.tokens_burnt,
+            );
+        }
+        let _ = writeln!(
+            table,
+            "| **Total** | | {} | {} | {} |",
+            self.transactions, self.total_gas, self.tokens_burnt,
+        );
+        table
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::{Gas, GasMeter};
+use crate::error::ErrorKind;
+use crate::result::{ExecutionFinalResult, Result};
+
+/// Environment variable that, when set to anything other than `0` or `false`, makes
+/// [`GasSnapshot::check`] write the recorded measurements to the baseline file instead of
+/// comparing against it.
+pub const UPDATE_GAS_SNAPSHOTS_ENV: &str = "NEAR_WORKSPACES_UPDATE_GAS_SNAPSHOTS";
+
+/// Default tolerance of a measurement, in percent of its baseline.
+const DEFAULT_TOLERANCE: f64 = 5.0;
+
+/// Named gas measurements checked against a baseline file, to catch regressions in the
+/// gas used by contract methods. The baseline is a JSON object mapping every measurement
+/// name to its gas, which is meant to be committed alongside the tests.
+///
+/// Measurements that burn more gas than their baseline plus the tolerance fail the check,
+/// as do measurements missing from the baseline. Run the tests with the environment
+/// variable [`UPDATE_GAS_SNAPSHOTS_ENV`] set to write the current measurements to the
+/// baseline instead. Entries of the baseline that were not recorded are left untouched,
+/// so multiple tests can share a baseline file as long as they do not update it at the
+/// same time.
+///
+/// Example
+/// ```rust, ignore, no_run
+/// let mut snapshot = GasSnapshot::new("tests/gas-baseline.json").tolerance(2.0);
+///
+/// let txn = contract.call("set_status").args_json(args).transact().await?;
+/// snapshot.record_result("set_status", &txn);
+/// snapshot.record_meter("full_flow", &meter)?;
+///
+/// snapshot.check()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct GasSnapshot {
+    path: PathBuf,
+    tolerance: f64,
+    tolerances: BTreeMap<String, f64>,
+    measurements: BTreeMap<String, Gas>,
+}
+
+impl GasSnapshot {
+    /// Create a snapshot compared against the baseline file at `path`, with a default
+    /// tolerance of 5%.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            tolerance: DEFAULT_TOLERANCE,
+            tolerances: BTreeMap::new(),
+            measurements: BTreeMap::new(),
+        }
+    }
+
+    /// Set the tolerance of all measurements, in percent of their baseline. A tolerance of
+    /// `0.0` fails on any increase in gas.
+    pub fn tolerance(mut self, percent: f64) -> Self {
+        self.tolerance = percent;
+        self
+    }
+
+    /// Set the tolerance of the measurement `name`, in percent of its baseline. This
+    /// overrides the tolerance set through [`GasSnapshot::tolerance`].
+    pub fn tolerance_for(mut self, name: impl Into<String>, percent: f64) -> Self {
+        self.tolerances.insert(name.into(), percent);
+        self
+    }
+
+    /// Record `gas` as the measurement `name`, replacing any previous measurement of it.
+    pub fn record(&mut self, name: impl Into<String>, gas: Gas) -> &mut Self {
+        self.measurements.insert(name.into(), gas);
+        self
+    }
+
+    /// Record the gas burnt by a transaction and all of its receipts as the measurement `name`.
+    pub fn record_result(
+        &mut self,
+        name: impl Into<String>,
+        result: &ExecutionFinalResult,
+    ) -> &mut Self {
+        self.record(name, result.total_gas_burnt)
+    }
+
+    /// Record the gas consumed so far by the transactions metered by `meter` as the
+    /// measurement `name`.
+    pub fn record_meter(&mut self, name: impl Into<String>, meter: &GasMeter) -> Result<&mut Self> {
+        let gas = meter.elapsed()?;
+        Ok(self.record(name, gas))
+    }
+
+    /// Measurements recorded so far.
+    pub fn measurements(&self) -> &BTreeMap<String, Gas> {
+        &self.measurements
+    }
+
+    fn read_baseline(&self) -> Result<BTreeMap<String, u64>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let baseline = std::fs::read_to_string(&self.path).map_err(|e| {
+            ErrorKind::Io.full(
+                format!("failed to read gas baseline {}", self.path.display()),
+                e,
+            )
+        })?;
+        serde_json::from_str(&baseline).map_err(|e| {
+            ErrorKind::DataConversion
+                .full(format!("malformed gas baseline {}", self.path.display()), e)
+        })
+    }
+
+    /// Compare the recorded measurements against the baseline file, without failing on
+    /// regressions. A missing baseline file is treated as an empty baseline.
+    pub fn compare(&self) -> Result<GasSnapshotDiff> {
+        let baseline = self.read_baseline()?;
+        let entries = self
+            .measurements
+            .iter()
+            .map(|(name, gas)| GasSnapshotEntry {
+                name: name.clone(),
+                baseline: baseline.get(name).copied().map(Gas::from_gas),
+                current: *gas,
+                tolerance: self.tolerances.get(name).copied().unwrap_or(self.tolerance),
+            })
+            .collect();
+
+        Ok(GasSnapshotDiff { entries })
+    }
+
+    /// Write the recorded measurements to the baseline file, keeping the entries of the
+    /// baseline that were not recorded.
+    pub fn update(&self) -> Result<()> {
+        let mut baseline = self.read_baseline()?;
+        baseline.extend(
+            self.measurements
+                .iter()
+                .map(|(name, gas)| (name.clone(), gas.as_gas())),
+        );
+
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| ErrorKind::Io.custom(e))?;
+        }
+        let baseline = serde_json::to_string_pretty(&baseline)
+            .map_err(|e| ErrorKind::DataConversion.custom(e))?;
+        std::fs::write(&self.path, baseline + "\n").map_err(|e| {
+            ErrorKind::Io.full(
+                format!("failed to write gas baseline {}", self.path.display()),
+                e,
+            )
+        })
+    }
+
+    /// Check the recorded measurements against the baseline file. Returns an error of kind
+    /// [`ErrorKind::GasRegression`] containing the diff table if any measurement regressed
+    /// or is missing from the baseline.
+    ///
+    /// If [`UPDATE_GAS_SNAPSHOTS_ENV`] is set, the baseline is updated with the recorded
+    /// measurements instead, and the diff against the previous baseline is returned.
+    pub fn check(&self) -> Result<GasSnapshotDiff> {
+        let diff = self.compare()?;
+        if update_requested() {
+            self.update()?;
+            return Ok(diff);
+        }
+
+        let problem = match (diff.has_regressions(), diff.has_new_entries()) {
+            (false, false) => return Ok(diff),
+            (true, false) => "gas usage regressed against",
+            (false, true) => "gas usage has no baseline in",
+            (true, true) => "gas usage regressed or has no baseline in",
+        };
+        Err(ErrorKind::GasRegression.message(format!(
+            "{problem} {}, set {UPDATE_GAS_SNAPSHOTS_ENV}=1 to update the baseline:\n{diff}",
+            self.path.display()
+        )))
+    }
+}
+
+fn update_requested() -> bool {
+    std::env::var(UPDATE_GAS_SNAPSHOTS_ENV)
+        .map(|val| !matches!(val.as_str(), "" | "0" | "false"))
+        .unwrap_or(false)
+}
+
+/// Outcome of comparing a single measurement against its baseline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GasSnapshotStatus {
+    /// The measurement is within the tolerance of its baseline.
+    Unchanged,
+    /// The measurement burnt more gas than its baseline plus the tolerance.
+    Regressed,
+    /// The measurement burnt less gas than its baseline minus the tolerance.
+    Improved,
+    /// The measurement has no baseline yet.
+    New,
+}
+
+/// A single measurement compared against its baseline.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct GasSnapshotEntry {
+    /// Name of the measurement.
+    pub name: String,
+    /// Gas of the measurement in the baseline, if it is in there.
+    pub baseline: Option<Gas>,
+    /// Gas of the recorded measurement.
+    pub current: Gas,
+    /// Tolerance of the measurement, in percent of its baseline.
+    pub tolerance: f64,
+}
+
+impl GasSnapshotEntry {
+    /// Change of the measurement relative to its baseline, in percent.
+    pub fn change_percent(&self) -> Option<f64> {
+        let baseline = self.baseline?.as_gas();
+        if baseline == 0 {
+            return Some(if self.current.as_gas() == 0 {
+                0.0
+            } else {
+                f64::INFINITY
+            });
+        }
+        Some((self.current.as_gas() as f64 - baseline as f64) / baseline as f64 * 100.0)
+    }
+
+    /// Whether this measurement is within the tolerance of its baseline, regressed,
+    /// improved or new.
+    pub fn status(&self) -> GasSnapshotStatus {
+        match self.change_percent() {
+            None => GasSnapshotStatus::New,
+            Some(change) if change > self.tolerance => GasSnapshotStatus::Regressed,
+            Some(change) if change < -self.tolerance => GasSnapshotStatus::Improved,
+            Some(_) => GasSnapshotStatus::Unchanged,
+        }
+    }
+}
+
+/// The recorded measurements of a [`GasSnapshot`] compared against its baseline. The
+/// [`Display`](fmt::Display) implementation renders a table of the measurements that
+/// changed beyond their tolerance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GasSnapshotDiff {
+    entries: Vec<GasSnapshotEntry>,
+}
+
+impl GasSnapshotDiff {
+    /// All the recorded measurements, ordered by name.
+    pub fn entries(&self) -> &[GasSnapshotEntry] {
+        &self.entries
+    }
+
+    fn with_status(&self, status: GasSnapshotStatus) -> Vec<&GasSnapshotEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status() == status)
+            .collect()
+    }
+
+    /// Measurements that burnt more gas than their baseline plus the tolerance.
+    pub fn regressions(&self) -> Vec<&GasSnapshotEntry> {
+        self.with_status(GasSnapshotStatus::Regressed)
+    }
+
+    /// Measurements that burnt less gas than their baseline minus the tolerance.
+    pub fn improvements(&self) -> Vec<&GasSnapshotEntry> {
+        self.with_status(GasSnapshotStatus::Improved)
+    }
+
+    /// Measurements that have no baseline yet.
+    pub fn new_entries(&self) -> Vec<&GasSnapshotEntry> {
+        self.with_status(GasSnapshotStatus::New)
+    }
+
+    /// Whether any measurement burnt more gas than its baseline plus the tolerance.
+    pub fn has_regressions(&self) -> bool {
+        !self.regressions().is_empty()
+    }
+
+    /// Whether any measurement has no baseline yet.
+    pub fn has_new_entries(&self) -> bool {
+        !self.new_entries().is_empty()
+    }
+}
+
+impl fmt::Display for GasSnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<40} {:>20} {:>20} {:>10} {:>10}  status",
+            "name", "baseline", "current", "change", "tolerance"
+        )?;
+        for entry in &self.entries {
+            let status = match entry.status() {
+                GasSnapshotStatus::Unchanged => continue,
+                GasSnapshotStatus::Regressed => "regressed",
+                GasSnapshotStatus::Improved => "improved",
+                GasSnapshotStatus::New => "new",
+            };
+            let baseline = entry
+                .baseline
+                .map_or_else(|| "-".to_string(), |gas| gas.as_gas().to_string());
+            let change = entry
+                .change_percent()
+                .map_or_else(|| "-".to_string(), |change| format!("{change:+.2}%"));
+            writeln!(
+                f,
+                "{:<40} {:>20} {:>20} {:>10} {:>9}%  {status}",
+                entry.name,
+                baseline,
+                entry.current.as_gas(),
+                change,
+                entry.tolerance,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -4,10 +4,15 @@
 //! since those APIs are not yet stable. Once they are, we can directly reference them here, so no
 //! changes on the library consumer side is needed. Just keep using these types defined here as-is.
 
+pub(crate) mod abi;
 pub(crate) mod account;
 pub(crate) mod block;
 pub(crate) mod chunk;
+pub(crate) mod contract_code;
 pub(crate) mod gas_meter;
+pub(crate) mod gas_snapshot;
+mod seed_phrase;
+pub(crate) mod state;
 
 #[cfg(feature = "interop_sdk")]
 mod sdk;
@@ -28,9 +33,16 @@ use sha2::Digest;
 use crate::error::{Error, ErrorKind};
 use crate::result::Result;
 
+pub use self::abi::ContractAbi;
 pub use self::account::{AccountDetails, AccountDetailsPatch};
 pub use self::chunk::{Chunk, ChunkHeader};
-pub use self::gas_meter::GasMeter;
+pub use self::contract_code::{ContractCode, ContractImport};
+pub use self::gas_meter::{CallGasUsage, GasMeter, GasReport};
+pub use self::gas_snapshot::{
+    GasSnapshot, GasSnapshotDiff, GasSnapshotEntry, GasSnapshotStatus, UPDATE_GAS_SNAPSHOTS_ENV,
+};
+pub use self::seed_phrase::DEFAULT_HD_PATH;
+pub use self::state::{ContractState, ContractStateExt};
 
 /// Nonce is a unit used to determine the order of transactions in the pool.
 pub type Nonce = u64;
@@ -41,6 +53,10 @@ pub use near_gas::NearGas as Gas;
 
 pub use near_token::NearToken;
 
+/// Execution level of a transaction to wait for when sending it to the network. See
+/// [`Transaction::wait_until`](crate::operations::Transaction::wait_until) for usage.
+pub use near_primitives::views::TxExecutionStatus;
+
 /// Height of a specific block
 pub type BlockHeight = u64;
 
@@ -175,6 +191,21 @@ impl PublicKey {
     pub fn key_data(&self) -> &[u8] {
         self.0.key_data()
     }
+
+    /// Get the implicit [`AccountId`] derived from this public key. ED25519 keys derive
+    /// NEAR-implicit ids, the 64 character hex encoding of the key. SECP256K1 keys derive
+    /// ETH-implicit ids, a `0x` prefixed hex encoding of the last 20 bytes of the keccak256
+    /// hash of the key.
+    pub fn implicit_account_id(&self) -> AccountId {
+        match &self.0 {
+            near_crypto::PublicKey::ED25519(pk) => {
+                near_primitives::utils::derive_near_implicit_account_id(pk)
+            }
+            near_crypto::PublicKey::SECP256K1(pk) => {
+                near_primitives::utils::derive_eth_implicit_account_id(pk)
+            }
+        }
+    }
 }
 
 impl Display for PublicKey {
@@ -243,6 +274,12 @@ impl SecretKey {
         PublicKey(self.0.public_key())
     }
 
+    /// Get the implicit [`AccountId`] derived from the public key of this secret key. See
+    /// [`PublicKey::implicit_account_id`] for how the id is derived.
+    pub fn implicit_account_id(&self) -> AccountId {
+        self.public_key().implicit_account_id()
+    }
+
     /// Generate a new secret key provided the [`KeyType`] and seed.
     pub fn from_seed(key_type: KeyType, seed: &str) -> Self {
         let key_type = key_type.into_near_keytype();
@@ -297,6 +334,11 @@ impl InMemorySigner {
         ))
     }
 
+    /// The id of the account this signer signs for.
+    pub fn account_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
     pub(crate) fn inner(&self) -> Signer {
         Signer::InMemory(near_crypto::InMemorySigner::from_secret_key(
             self.account_id.clone(),
@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+use bip39::Mnemonic;
+use slip10::BIP32Path;
+
+use super::SecretKey;
+use crate::error::ErrorKind;
+use crate::result::Result;
+
+/// Derivation path used by near-cli and wallets to derive the key of a seed phrase.
+pub const DEFAULT_HD_PATH: &str = "m/44'/397'/0'";
+
+/// Number of words in seed phrases generated by [`SecretKey::generate_seed_phrase`].
+const SEED_PHRASE_WORD_COUNT: usize = 12;
+
+impl SecretKey {
+    /// Derive the ED25519 secret key of a BIP-39 seed phrase, following SLIP-10 along the
+    /// given derivation path. Using [`DEFAULT_HD_PATH`] gives the same key as near-cli and
+    /// wallets do for the phrase.
+    ///
+    /// The phrase is normalized the same way near-seed-phrase does, so surrounding and
+    /// repeated whitespace as well as uppercase letters do not change the derived key.
+    pub fn from_seed_phrase(phrase: &str, hd_path: &str) -> Result<Self> {
+        let phrase = phrase
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mnemonic = Mnemonic::parse_normalized(&phrase)
+            .map_err(|e| ErrorKind::DataConversion.full("invalid seed phrase", e))?;
+        let path = BIP32Path::from_str(hd_path).map_err(|e| {
+            ErrorKind::DataConversion.message(format!("invalid HD path {hd_path}: {e:?}"))
+        })?;
+
+        let derived =
+            slip10::derive_key_from_path(&mnemonic.to_seed(""), slip10::Curve::Ed25519, &path)
+                .map_err(|e| {
+                    ErrorKind::DataConversion
+                        .message(format!("failed to derive key at {hd_path}: {e:?}"))
+                })?;
+
+        let keypair = ed25519_dalek::SigningKey::from_bytes(&derived.key).to_keypair_bytes();
+        Ok(Self(near_crypto::SecretKey::ED25519(
+            near_crypto::ED25519SecretKey(keypair),
+        )))
+    }
+
+    /// Generate a new 12 word seed phrase using OS provided entropy, along with its secret
+    /// key derived at [`DEFAULT_HD_PATH`].
+    pub fn generate_seed_phrase() -> (String, Self) {
+        let phrase = Mnemonic::generate(SEED_PHRASE_WORD_COUNT)
+            .expect("12 words is a valid seed phrase length")
+            .to_string();
+        let sk = Self::from_seed_phrase(&phrase, DEFAULT_HD_PATH)
+            .expect("generated seed phrase should be valid");
+        (phrase, sk)
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+#[cfg(feature = "interop_sdk")]
+use std::convert::TryInto;
+use std::ops::{Deref, DerefMut};
+
+use near_primitives::borsh::BorshDeserialize;
+#[cfg(feature = "interop_sdk")]
+use sha2::{Digest, Sha256};
+
+use crate::error::ErrorKind;
+use crate::result::{display_key, Result};
+
+/// Raw key value state of a contract, such as the state before and after an upgrade. It
+/// offers the helpers of [`ContractStateExt`] without having to import it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContractState(HashMap<Vec<u8>, Vec<u8>>);
+
+impl ContractState {
+    /// Grab the underlying map of storage keys to values.
+    pub fn into_inner(self) -> HashMap<Vec<u8>, Vec<u8>> {
+        self.0
+    }
+
+    /// Decode the Borsh value stored under `key`, see [`ContractStateExt::decode`].
+    pub fn decode<T: BorshDeserialize>(&self, key: &[u8]) -> Result<T> {
+        self.0.decode(key)
+    }
+
+    /// Decode the entries of a near-sdk `LookupMap`, see [`ContractStateExt::lookup_map`].
+    #[cfg(feature = "interop_sdk")]
+    pub fn lookup_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize,
+    {
+        self.0.lookup_map(prefix)
+    }
+
+    /// Decode the entries of a near-sdk `IterableMap`, see
+    /// [`ContractStateExt::iterable_map`].
+    #[cfg(feature = "interop_sdk")]
+    pub fn iterable_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize,
+    {
+        self.0.iterable_map(prefix)
+    }
+
+    /// Decode the entries of a near-sdk `UnorderedMap`, see
+    /// [`ContractStateExt::unordered_map`].
+    #[cfg(feature = "interop_sdk")]
+    pub fn unordered_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize,
+    {
+        self.0.unordered_map(prefix)
+    }
+
+    /// Decode the elements of a near-sdk `Vector`, see [`ContractStateExt::vector`].
+    #[cfg(feature = "interop_sdk")]
+    pub fn vector<T: BorshDeserialize>(
+        &self,
+        prefix: impl near_sdk::IntoStorageKey,
+    ) -> Result<Vec<T>> {
+        self.0.vector(prefix)
+    }
+}
+
+/// Helpers to decode the Borsh values near-sdk contracts store, for the raw key value state
+/// returned by [`Contract::view_state`]:
+///
+/// ```ignore
+/// use near_workspaces::types::ContractStateExt;
+///
+/// let status: StatusMessage = contract.view_state().await?.decode(b"STATE")?;
+/// ```
+///
+/// With the `interop_sdk` feature, the entries of near-sdk [`store`] collections can be
+/// decoded as well, given the prefix the collection was created with.
+///
+/// [`Contract::view_state`]: crate::Contract::view_state
+/// [`store`]: https://docs.rs/near-sdk/latest/near_sdk/store/index.html
+pub trait ContractStateExt {
+    /// Decode the Borsh value stored under `key`, such as the `STATE` key near-sdk stores
+    /// the contract struct under. Fails if there is no such key.
+    fn decode<T: BorshDeserialize>(&self, key: &[u8]) -> Result<T>;
+
+    /// Decode the entries of a near-sdk [`LookupMap`] created with `prefix`, sorted by their
+    /// storage key. Only maps using the default `Identity` hasher can be decoded, since the
+    /// keys of other maps are hashed.
+    ///
+    /// [`LookupMap`]: near_sdk::store::LookupMap
+    #[cfg(feature = "interop_sdk")]
+    fn lookup_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize;
+
+    /// Decode the entries of a near-sdk [`IterableMap`] created with `prefix`, in the order
+    /// the map iterates over them. Maps using the default `Sha256` hasher or the `Identity`
+    /// one can be decoded, but not those using `Keccak256`.
+    ///
+    /// [`IterableMap`]: near_sdk::store::IterableMap
+    #[cfg(feature = "interop_sdk")]
+    fn iterable_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize;
+
+    /// Decode the entries of a near-sdk [`UnorderedMap`] created with `prefix`, in the order
+    /// the map iterates over them. Maps using the default `Sha256` hasher or the `Identity`
+    /// one can be decoded, but not those using `Keccak256`.
+    ///
+    /// [`UnorderedMap`]: near_sdk::store::UnorderedMap
+    #[cfg(feature = "interop_sdk")]
+    fn unordered_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize;
+
+    /// Decode the elements of a near-sdk [`Vector`] created with `prefix`, in order.
+    ///
+    /// [`Vector`]: near_sdk::store::Vector
+    #[cfg(feature = "interop_sdk")]
+    fn vector<T: BorshDeserialize>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<T>>;
+}
+
+impl ContractStateExt for HashMap<Vec<u8>, Vec<u8>> {
+    fn decode<T: BorshDeserialize>(&self, key: &[u8]) -> Result<T> {
+        let value = self.get(key).ok_or_else(|| {
+            ErrorKind::DataConversion.message(format!(
+                "no value stored under the key {} of the contract state",
+                display_key(key)
+            ))
+        })?;
+        decode_value(key, value)
+    }
+
+    #[cfg(feature = "interop_sdk")]
+    fn lookup_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize,
+    {
+        let prefix = prefix.into_storage_key();
+        let mut entries = entries_with_prefix(self, &prefix);
+        entries.sort_by_key(|(key, _, _)| *key);
+        entries
+            .into_iter()
+            .map(|(key, suffix, value)| Ok((decode_value(key, suffix)?, decode_value(key, value)?)))
+            .collect()
+    }
+
+    #[cfg(feature = "interop_sdk")]
+    fn iterable_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize,
+    {
+        // The keys are kept in a `Vector` under `prefix + b"v"`, in the order the map iterates
+        // over them, and the values in a `LookupMap` under `prefix + b"m"`.
+        let prefix = prefix.into_storage_key();
+        indexed_entries(self, &[prefix.as_slice(), b"v"].concat())
+            .into_iter()
+            .map(|(key, _, value)| map_entry(self, &prefix, key, value))
+            .collect()
+    }
+
+    #[cfg(feature = "interop_sdk")]
+    fn unordered_map<K, V>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<(K, V)>>
+    where
+        K: BorshDeserialize,
+        V: BorshDeserialize,
+    {
+        // Laid out like an `IterableMap`, except that its keys are kept in a `FreeList`, whose
+        // slots are either `Occupied(key)` or `Empty { .. }` where a key got removed.
+        let prefix = prefix.into_storage_key();
+        indexed_entries(self, &[prefix.as_slice(), b"v"].concat())
+            .into_iter()
+            .filter_map(|(key, _, slot)| match slot.split_first() {
+                Some((0, value)) => Some(map_entry(self, &prefix, key, value)),
+                Some((1, _)) => None,
+                _ => Some(Err(ErrorKind::DataConversion.message(format!(
+                    "the entry stored under the key {} of the contract state is not a slot of \
+                     the keys of an `UnorderedMap`",
+                    display_key(key)
+                )))),
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "interop_sdk")]
+    fn vector<T: BorshDeserialize>(&self, prefix: impl near_sdk::IntoStorageKey) -> Result<Vec<T>> {
+        let prefix = prefix.into_storage_key();
+        indexed_entries(self, &prefix)
+            .into_iter()
+            .map(|(key, _, value)| decode_value(key, value))
+            .collect()
+    }
+}
+
+impl Deref for ContractState {
+    type Target = HashMap<Vec<u8>, Vec<u8>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ContractState {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl IntoIterator for ContractState {
+    type Item = (Vec<u8>, Vec<u8>);
+    type IntoIter = std::collections::hash_map::IntoIter<Vec<u8>, Vec<u8>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl From<HashMap<Vec<u8>, Vec<u8>>> for ContractState {
+    fn from(state: HashMap<Vec<u8>, Vec<u8>>) -> Self {
+        Self(state)
+    }
+}
+
+impl From<ContractState> for HashMap<Vec<u8>, Vec<u8>> {
+    fn from(state: ContractState) -> Self {
+        state.0
+    }
+}
+
+/// Entries whose key starts with `prefix`, as the full key, the rest of the key after
+/// the prefix and the value.
+#[cfg(feature = "interop_sdk")]
+fn entries_with_prefix<'a>(
+    state: &'a HashMap<Vec<u8>, Vec<u8>>,
+    prefix: &[u8],
+) -> Vec<(&'a [u8], &'a [u8], &'a [u8])> {
+    state
+        .iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(prefix)
+                .map(|suffix| (key.as_slice(), suffix, value.as_slice()))
+        })
+        .collect()
+}
+
+/// Entries stored under `prefix` followed by a little endian u32 index, the way
+/// near-sdk collections store their elements, sorted by their index.
+#[cfg(feature = "interop_sdk")]
+fn indexed_entries<'a>(
+    state: &'a HashMap<Vec<u8>, Vec<u8>>,
+    prefix: &[u8],
+) -> Vec<(&'a [u8], u32, &'a [u8])> {
+    let mut entries = entries_with_prefix(state, prefix)
+        .into_iter()
+        .filter_map(|(key, suffix, value)| {
+            let index = u32::from_le_bytes(suffix.try_into().ok()?);
+            Some((key, index, value))
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(_, index, _)| *index);
+    entries
+}
+
+/// Decode the entry of an `IterableMap` or `UnorderedMap` created with `prefix`, out of
+/// `key_bytes` found under `storage_key` among the keys of the map. Values are stored
+/// along with the index of their key, under `prefix + b"m"` followed by the key, or by
+/// the hash of both with the `Sha256` hasher.
+#[cfg(feature = "interop_sdk")]
+fn map_entry<K, V>(
+    state: &HashMap<Vec<u8>, Vec<u8>>,
+    prefix: &[u8],
+    storage_key: &[u8],
+    key_bytes: &[u8],
+) -> Result<(K, V)>
+where
+    K: BorshDeserialize,
+    V: BorshDeserialize,
+{
+    let value_key = [prefix, b"m", key_bytes].concat();
+    let hashed_key = Sha256::digest(&value_key).to_vec();
+    let (value_key, value) = vec![value_key, hashed_key]
+        .into_iter()
+        .find_map(|value_key| {
+            let value = state.get(&value_key)?;
+            Some((value_key, value))
+        })
+        .ok_or_else(|| {
+            ErrorKind::DataConversion.message(format!(
+                "no value stored for the map key under {} of the contract state",
+                display_key(storage_key)
+            ))
+        })?;
+    let (value, _index): (V, u32) = decode_value(&value_key, value)?;
+    Ok((decode_value(storage_key, key_bytes)?, value))
+}
+
+/// Decode `bytes` found under the storage `key`, which is only used to report failures.
+fn decode_value<T: BorshDeserialize>(key: &[u8], bytes: &[u8]) -> Result<T> {
+    T::try_from_slice(bytes).map_err(|e| {
+        ErrorKind::DataConversion.full(
+            format!(
+                "could not decode the entry stored under the key {} of the contract state: {e}",
+                display_key(key)
+            ),
+            e,
+        )
+    })
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::error::Error;
+use crate::network::NetworkClient;
+use crate::result::Result;
+use crate::types::{AccountId, InMemorySigner};
+use crate::worker::Worker;
+use crate::Network;
+
+/// Outcome of deleting the accounts created by a [`Worker`] through [`Worker::cleanup`].
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct CleanupReport {
+    /// Accounts that were deleted.
+    pub deleted: Vec<AccountId>,
+    /// Accounts that could not be deleted, along with the reason. These are still tracked
+    /// by the worker, so a later cleanup retries them.
+    pub failed: Vec<(AccountId, Error)>,
+}
+
+impl CleanupReport {
+    /// Whether every tracked account was deleted.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+impl fmt::Display for CleanupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "deleted {} accounts", self.deleted.len())?;
+        if !self.failed.is_empty() {
+            write!(f, ", failed to delete {}:", self.failed.len())?;
+            for (id, err) in &self.failed {
+                write!(f, "\n  {id}: {err}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: ?Sized> Worker<T> {
+    /// Track an account created through this worker, so it gets deleted on cleanup.
+    pub(crate) fn track_account(&self, signer: &InMemorySigner) -> Result<()> {
+        self.created_accounts.lock()?.push(signer.clone());
+        Ok(())
+    }
+
+    /// Ids of the accounts created through this worker, or any of its clones, which have not
+    /// been deleted by [`Worker::cleanup`] yet. This covers dev and top level accounts,
+    /// subaccounts, and implicit accounts.
+    pub fn created_accounts(&self) -> Result<Vec<AccountId>> {
+        let accounts = self.created_accounts.lock()?;
+        Ok(accounts
+            .iter()
+            .map(|signer| signer.account_id.clone())
+            .collect())
+    }
+}
+
+impl<T> Worker<T>
+where
+    T: NetworkClient + Send + Sync + ?Sized,
+{
+    /// Delete every account created through this worker, sending their remaining balance to
+    /// `beneficiary_id`. Accounts are deleted in the reverse order of their creation, and a
+    /// failure to delete one does not stop the others from being deleted. This is useful on
+    /// live networks such as testnet, where dev accounts otherwise stay around with tokens
+    /// locked in them.
+    pub async fn cleanup(&self, beneficiary_id: &AccountId) -> Result<CleanupReport> {
+        let accounts = std::mem::take(&mut *self.created_accounts.lock()?);
+
+        let mut report = CleanupReport::default();
+        let mut remaining = Vec::new();
+        for signer in accounts.into_iter().rev() {
+            let id = signer.account_id.clone();
+            let deleted = self
+                .delete_account(&id, &signer, beneficiary_id)
+                .await
+                .and_then(|outcome| outcome.into_result().map_err(Error::from));
+
+            match deleted {
+                Ok(_) => report.deleted.push(id),
+                Err(err) => {
+                    tracing::warn!(target: "workspaces", "failed to delete account {id}: {err}");
+                    report.failed.push((id, err));
+                    remaining.push(signer);
+                }
+            }
+        }
+
+        // Failed accounts go back to the front, ahead of any tracked in the meantime, so the
+        // creation order is kept.
+        remaining.reverse();
+        let mut accounts = self.created_accounts.lock()?;
+        remaining.append(&mut accounts);
+        *accounts = remaining;
+        Ok(report)
+    }
+}
+
+impl<T> Worker<T>
+where
+    T: Network + 'static,
+{
+    /// Delete every account created through this worker once the returned guard is dropped,
+    /// sending their remaining balance to `beneficiary_id`. See [`Worker::cleanup`].
+    ///
+    /// This is best-effort: the deletions are awaited on drop when running within a
+    /// multi-threaded tokio runtime. Within a current thread runtime, such as the default
+    /// one of `#[tokio::test]`, they are only spawned and might not complete before the
+    /// runtime shuts down. Prefer calling [`Worker::cleanup`] explicitly where possible.
+    pub fn cleanup_on_drop(&self, beneficiary_id: AccountId) -> CleanupGuard {
+        CleanupGuard {
+            worker: self.clone().coerce(),
+            beneficiary_id,
+        }
+    }
+}
+
+/// Deletes the accounts created by a [`Worker`] when dropped. Created through
+/// [`Worker::cleanup_on_drop`].
+#[must_use = "accounts are cleaned up when the guard is dropped"]
+pub struct CleanupGuard {
+    worker: Worker<dyn Network>,
+    beneficiary_id: AccountId,
+}
+
+impl fmt::Debug for CleanupGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CleanupGuard")
+            .field("beneficiary_id", &self.beneficiary_id)
+            .finish()
+    }
+}
+
+impl Drop for CleanupGuard {
+    fn drop(&mut self) {
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(
+                target: "workspaces",
+                "no tokio runtime to clean up the created accounts with"
+            );
+            return;
+        };
+
+        let worker = self.worker.clone();
+        let beneficiary_id = self.beneficiary_id.clone();
+        let cleanup = async move {
+            match worker.cleanup(&beneficiary_id).await {
+                Ok(report) if report.is_success() => {}
+                Ok(report) => tracing::warn!(target: "workspaces", "cleanup incomplete: {report}"),
+                Err(err) => tracing::warn!(target: "workspaces", "cleanup failed: {err}"),
+            }
+        };
+
+        match handle.runtime_flavor() {
+            tokio::runtime::RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(cleanup))
+            }
+            _ => {
+                handle.spawn(cleanup);
+            }
+        }
+    }
+}
@@ -3,14 +3,14 @@ use near_primitives::views::StatusResponse;
 use crate::network::{Info, RootAccountSubaccountCreator, Sandbox, Testnet};
 use crate::network::{NetworkClient, NetworkInfo};
 use crate::operations::{CallTransaction, Function};
-use crate::result::{ExecutionFinalResult, Result};
+use crate::result::{Execution, ExecutionFinalResult, Result};
 use crate::rpc::client::Client;
 use crate::rpc::patch::{ImportContractTransaction, PatchTransaction};
 use crate::rpc::query::{
     GasPrice, Query, QueryChunk, ViewAccessKey, ViewAccessKeyList, ViewAccount, ViewBlock,
     ViewCode, ViewFunction, ViewState,
 };
-use crate::types::{AccountId, InMemorySigner, NearToken, PublicKey};
+use crate::types::{AccountId, InMemorySigner, NearToken, PublicKey, SecretKey};
 use crate::worker::Worker;
 use crate::{Account, Network};
 
@@ -37,6 +37,8 @@ impl<T: ?Sized> Clone for Worker<T> {
         Self {
             workspace: self.workspace.clone(),
             tx_callbacks: self.tx_callbacks.clone(),
+            created_accounts: self.created_accounts.clone(),
+            estimation_sandbox: self.estimation_sandbox.clone(),
         }
     }
 }
@@ -48,6 +50,10 @@ where
     fn info(&self) -> &Info {
         self.workspace.info()
     }
+
+    fn is_sandbox(&self) -> bool {
+        self.workspace.is_sandbox()
+    }
 }
 
 impl<T> Worker<T>
@@ -75,6 +81,7 @@ where
             ViewFunction {
                 account_id: contract_id.clone(),
                 function,
+                abi: None,
             },
         )
     }
@@ -91,7 +98,8 @@ where
 
     /// View the state of a account/contract on the network. This will return the internal
     /// state of the account in the form of a map of key-value pairs; where STATE contains
-    /// info on a contract's internal data.
+    /// info on a contract's internal data, which can be decoded through
+    /// [`ContractStateExt`](crate::types::ContractStateExt).
     pub fn view_state(&self, contract_id: &AccountId) -> Query<'_, ViewState> {
         Query::view_state(self.client(), contract_id)
     }
@@ -198,6 +206,12 @@ where
     pub async fn status(&self) -> Result<StatusResponse> {
         self.client().status().await
     }
+
+    /// Maximum size in bytes of contract code that can be deployed, as currently set by the
+    /// protocol config of the network.
+    pub async fn max_contract_size(&self) -> Result<u64> {
+        self.client().max_contract_size().await
+    }
 }
 
 #[cfg(feature = "experimental")]
@@ -309,6 +323,19 @@ impl Worker<Sandbox> {
         Ok(Account::new(signer, self.clone().coerce()))
     }
 
+    /// Create the NEAR-implicit account derived from `sk`, funded with `amount` by the root
+    /// account. See [`Account::create_implicit_account`] to fund it from any other account,
+    /// which also works on networks other than sandbox.
+    pub async fn create_implicit_account(
+        &self,
+        sk: SecretKey,
+        amount: NearToken,
+    ) -> Result<Execution<Account>> {
+        self.root_account()?
+            .create_implicit_account(sk, amount)
+            .await
+    }
+
     /// Import a contract from the the given network, and return us a [`ImportContractTransaction`]
     /// which allows to specify further details, such as being able to import contract data and
     /// how far back in time we wanna grab the contract.
@@ -0,0 +1,119 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::ErrorKind;
+use crate::network::NetworkInfo;
+use crate::result::Result;
+use crate::types::{AccountId, InMemorySigner};
+use crate::worker::Worker;
+use crate::{Account, Network};
+
+impl<T> Worker<T>
+where
+    T: Network + 'static,
+{
+    /// Directories searched for credentials of this network, in order: the network's
+    /// `keystore_path` and then the same path under the home directory, which is where
+    /// near-cli stores credentials (e.g. `~/.near-credentials/testnet/`).
+    fn keystore_dirs(&self) -> Vec<PathBuf> {
+        let keystore_path = &self.info().keystore_path;
+        let mut dirs = vec![keystore_path.clone()];
+        if keystore_path.is_relative() {
+            if let Some(home) = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))
+            {
+                dirs.push(PathBuf::from(home).join(keystore_path));
+            }
+        }
+        dirs
+    }
+
+    /// Load an [`Account`] from the credentials stored for `id` in the keystore of this
+    /// network. This looks into the network's `keystore_path` first, and then into
+    /// `~/.near-credentials/<network>/`.
+    ///
+    /// Credentials are read in the near-cli format, either from `<id>.json` or from any
+    /// key file in a `<id>/` directory, as stored by newer versions of near-cli. Key files
+    /// may hold the key under either `secret_key` or `private_key`.
+    pub fn account_from_keystore(&self, id: &AccountId) -> Result<Account> {
+        let dirs = self.keystore_dirs();
+        for dir in &dirs {
+            if let Some(signer) = signer_from_dir(dir, id)? {
+                return Ok(Account::new(signer, self.clone().coerce()));
+            }
+        }
+
+        Err(ErrorKind::Io.message(format!(
+            "no credentials for {id} found in {}",
+            dirs.iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))
+    }
+
+    /// List the ids of all accounts with credentials in the keystore of this network. See
+    /// [`Worker::account_from_keystore`] for the directories searched.
+    pub fn keystore_accounts(&self) -> Result<Vec<AccountId>> {
+        let mut accounts = BTreeSet::new();
+        for dir in self.keystore_dirs() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => {
+                    return Err(ErrorKind::Io
+                        .full(format!("failed to read keystore {}", dir.display()), err))
+                }
+            };
+
+            for entry in entries {
+                let path = entry.map_err(|e| ErrorKind::Io.custom(e))?.path();
+                let id = if path.is_dir() {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .and_then(|name| name.parse::<AccountId>().ok())
+                        .filter(|id| matches!(signer_from_dir(&dir, id), Ok(Some(_))))
+                } else if is_json(&path) {
+                    InMemorySigner::from_file(&path)
+                        .ok()
+                        .map(|signer| signer.account_id)
+                } else {
+                    None
+                };
+                accounts.extend(id);
+            }
+        }
+
+        Ok(accounts.into_iter().collect())
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+/// Grab the signer of `id` out of a keystore directory, looking for `<id>.json` first and
+/// then for any key file of `id` within a `<id>/` directory.
+fn signer_from_dir(dir: &Path, id: &AccountId) -> Result<Option<InMemorySigner>> {
+    let path = dir.join(format!("{id}.json"));
+    if path.is_file() {
+        return InMemorySigner::from_file(&path).map(Some);
+    }
+
+    let account_dir = dir.join(id.as_str());
+    if !account_dir.is_dir() {
+        return Ok(None);
+    }
+
+    let mut key_files = std::fs::read_dir(&account_dir)
+        .map_err(|e| ErrorKind::Io.custom(e))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| ErrorKind::Io.custom(e))?;
+    key_files.sort();
+
+    Ok(key_files
+        .iter()
+        .filter(|path| is_json(path))
+        .filter_map(|path| InMemorySigner::from_file(path).ok())
+        .find(|signer| &signer.account_id == id))
+}
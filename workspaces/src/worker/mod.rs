@@ -1,13 +1,19 @@
+mod cleanup;
 mod impls;
+mod keystore;
 
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::network::builder::NetworkBuilder;
 use crate::network::{Betanet, Custom, Mainnet, Sandbox, Testnet};
+use crate::rpc::estimate::EstimationSandbox;
 use crate::types::gas_meter::GasHook;
+use crate::types::InMemorySigner;
 use crate::{Network, Result};
 
+pub use self::cleanup::{CleanupGuard, CleanupReport};
+
 /// The `Worker` type allows us to interact with any NEAR related networks,
 /// such as mainnet and testnet.
 ///
@@ -17,6 +23,11 @@ use crate::{Network, Result};
 pub struct Worker<T: ?Sized> {
     pub(crate) workspace: Arc<T>,
     pub(crate) tx_callbacks: Vec<GasHook>,
+    /// Accounts created through this worker, shared with all of its clones.
+    pub(crate) created_accounts: Arc<Mutex<Vec<InMemorySigner>>>,
+    /// Sandbox transactions against networks other than sandbox are dry-run in to estimate
+    /// their costs, shared with all of its clones. Spawned on the first estimation.
+    pub(crate) estimation_sandbox: Arc<tokio::sync::Mutex<Option<EstimationSandbox>>>,
 }
 
 impl<T> Worker<T>
@@ -27,6 +38,8 @@ where
         Self {
             workspace: Arc::new(network),
             tx_callbacks: vec![],
+            created_accounts: Arc::new(Mutex::new(Vec::new())),
+            estimation_sandbox: Arc::new(tokio::sync::Mutex::new(None)),
         }
     }
 }
@@ -36,6 +49,8 @@ impl<T: Network + 'static> Worker<T> {
         Worker {
             workspace: self.workspace,
             tx_callbacks: self.tx_callbacks,
+            created_accounts: self.created_accounts,
+            estimation_sandbox: self.estimation_sandbox,
         }
     }
 }
@@ -0,0 +1,132 @@
+use near_workspaces::error::ErrorKind;
+use near_workspaces::types::{ContractAbi, NearToken};
+use serde_json::json;
+use test_log::test;
+
+const ADDER_WASM_FILEPATH: &str = "../examples/res/adder.wasm";
+const ADDER_ABI_FILEPATH: &str = "../examples/res/adder.json";
+const ADDER_COMPRESSED_ABI_FILEPATH: &str = "../examples/res/adder.json.zst";
+
+#[test(tokio::test)]
+async fn test_abi_checks_views() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(ADDER_WASM_FILEPATH)?;
+    let abi = ContractAbi::from_file(ADDER_ABI_FILEPATH)?;
+    assert!(abi.function("add").is_some());
+
+    let contract = worker.dev_deploy(&wasm).await?.with_abi(abi);
+
+    let sum: (u32, u32) = contract
+        .view("add")
+        .args_json(json!({ "a": [1, 2], "b": [3, 4] }))
+        .await?
+        .json()?;
+    assert_eq!(sum, (4, 6));
+
+    let err = contract
+        .view("sub")
+        .args_json(json!({ "a": [1, 2], "b": [3, 4] }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Abi);
+    assert!(err.to_string().contains("available functions are: add"));
+
+    let err = contract
+        .view("add")
+        .args_json(json!({ "a": [1, 2] }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Abi);
+    assert!(err.to_string().contains("missing argument `b`"));
+
+    let err = contract
+        .view("add")
+        .args_json(json!({ "a": [1, 2], "b": "3, 4" }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Abi);
+
+    let err = contract
+        .view("add")
+        .args_json(json!({ "a": [1, 2], "b": [3, 4], "c": [5, 6] }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown argument `c`"));
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_abi_checks_calls() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(ADDER_WASM_FILEPATH)?;
+    let contract = worker
+        .dev_deploy(&wasm)
+        .await?
+        .with_abi(ContractAbi::from_file(ADDER_ABI_FILEPATH)?);
+
+    // `add` is not payable, so the deposit gets refused before the call is sent.
+    let err = contract
+        .call("add")
+        .args_json(json!({ "a": [1, 2], "b": [3, 4] }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Abi);
+    assert!(err.to_string().contains("not payable"));
+
+    contract
+        .call("add")
+        .args_json(json!({ "a": [1, 2], "b": [3, 4] }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    Ok(())
+}
+
+#[test]
+fn test_abi_from_compressed_bytes() -> anyhow::Result<()> {
+    let abi = ContractAbi::from_file(ADDER_ABI_FILEPATH)?;
+    let compressed = ContractAbi::from_file(ADDER_COMPRESSED_ABI_FILEPATH)?;
+    assert_eq!(
+        serde_json::to_value(compressed.root())?,
+        serde_json::to_value(abi.root())?
+    );
+
+    // Bytes starting with the zstd magic number are treated as compressed.
+    let err = ContractAbi::from_slice(&[0x28, 0xb5, 0x2f, 0xfd, 0, 0, 0]).unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Abi);
+    assert!(err.to_string().contains("decompress"));
+
+    Ok(())
+}
+
+#[cfg(feature = "unstable")]
+#[test(tokio::test)]
+async fn test_abi_embedded_into_contract() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    // cargo-near embeds the compressed ABI into the contract, served by `__contract_abi`.
+    let compiled = near_workspaces::CompileOptions::new("./tests/test-contracts/status-message")
+        .compile()
+        .await?;
+    let contract = worker.dev_deploy(&compiled.wasm).await?;
+
+    let abi = contract.abi().await?;
+    assert!(abi.function("set_status").is_some());
+    let generated = compiled.abi.expect("ABI should be generated");
+    assert_eq!(
+        serde_json::to_value(abi.root())?,
+        serde_json::to_value(generated.root())?
+    );
+
+    // Contracts built without an embedded ABI do not serve one.
+    let contract = worker
+        .dev_deploy(&std::fs::read(ADDER_WASM_FILEPATH)?)
+        .await?;
+    let err = contract.abi().await.unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Abi);
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 #![recursion_limit = "256"]
 use near_token::NearToken;
-use serde_json::{Map, Value};
+use near_workspaces::error::{AccessKeyErrorCode, ErrorKind};
+use near_workspaces::types::{KeyType, SecretKey};
+use near_workspaces::AccessKey;
+use serde_json::{json, Map, Value};
 use test_log::test;
 
 use std::fs::{self, File};
@@ -96,3 +99,308 @@ async fn test_delete_account() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_sign_with_function_call_key() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let account = worker.dev_create_account().await?;
+
+    let allowance = NearToken::from_millinear(250);
+    let sk = SecretKey::from_random(KeyType::ED25519);
+    account
+        .batch(account.id())
+        .add_key(
+            sk.public_key(),
+            AccessKey::function_call_access(contract.id(), &["set_status"], Some(allowance)),
+        )
+        .transact()
+        .await?
+        .into_result()?;
+
+    // An allowed method within the allowance succeeds, and consumes some of it.
+    let outcome = account
+        .call(contract.id(), "set_status")
+        .args_json(json!({ "message": "hello" }))
+        .sign_with(sk.clone())
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    let used = outcome
+        .allowance_used()
+        .expect("allowance should be tracked");
+    assert!(used > NearToken::from_yoctonear(0));
+    assert!(used <= allowance);
+
+    // Calls made by an account signing with the key report the allowance as well.
+    let outcome = account
+        .with_key(sk.clone())
+        .call(contract.id(), "set_status")
+        .args_json(json!({ "message": "hello again" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert!(outcome.allowance_used().is_some());
+
+    // A method that the key does not permit is rejected with a typed error.
+    let err = account
+        .with_key(sk)
+        .call(contract.id(), "get_status")
+        .args_json(json!({ "account_id": account.id() }))
+        .transact()
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ErrorKind::AccessKey(AccessKeyErrorCode::MethodNameMismatch)
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_account_from_keystore() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let keystore = dir.path();
+    let worker = near_workspaces::sandbox().keystore_path(keystore).await?;
+
+    // Credentials stored as `<id>.json`.
+    let account = worker.dev_create_account().await?;
+    account.store_credentials(keystore).await?;
+
+    // Credentials stored by near-cli under `<id>/<public key>.json`, with `private_key`.
+    let other = worker.dev_create_account().await?;
+    let other_dir = keystore.join(other.id().as_str());
+    fs::create_dir_all(&other_dir)?;
+    fs::write(
+        other_dir.join(format!("{}.json", other.secret_key().public_key())),
+        serde_json::to_string(&json!({
+            "account_id": other.id(),
+            "public_key": other.secret_key().public_key(),
+            "private_key": other.secret_key(),
+        }))?,
+    )?;
+
+    let loaded = worker.account_from_keystore(account.id())?;
+    assert_eq!(loaded.id(), account.id());
+    assert_eq!(loaded.secret_key(), account.secret_key());
+
+    let loaded = worker.account_from_keystore(other.id())?;
+    assert_eq!(loaded.secret_key(), other.secret_key());
+    loaded.view_account().await?;
+
+    // The keystore only holds what this test stored in it.
+    let mut expected = vec![account.id().clone(), other.id().clone()];
+    expected.sort();
+    assert_eq!(worker.keystore_accounts()?, expected);
+
+    let missing: near_workspaces::AccountId = "missing.test.near".parse()?;
+    let err = worker.account_from_keystore(&missing).unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Io);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_create_implicit_account() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+
+    let eth_sk = SecretKey::from_seed(KeyType::SECP256K1, "eth-implicit");
+    let eth_id = eth_sk.implicit_account_id();
+    assert!(eth_id.as_str().starts_with("0x"));
+    assert_eq!(eth_id.len(), 42);
+    // ETH-implicit accounts cannot sign with their key, so they are not handed out as one.
+    let result = worker
+        .create_implicit_account(eth_sk, NearToken::from_near(1))
+        .await;
+    assert!(matches!(result, Err(err) if err.kind() == &ErrorKind::Usage));
+
+    let sk = SecretKey::from_random(KeyType::ED25519);
+    let id = sk.implicit_account_id();
+    assert_eq!(id.len(), 64);
+    assert_eq!(id, sk.public_key().implicit_account_id());
+
+    let account = worker
+        .create_implicit_account(sk, NearToken::from_near(1))
+        .await?;
+    assert!(account.details.is_success());
+    let account = account.into_result()?;
+    assert_eq!(account.id(), &id);
+    assert_eq!(
+        account.view_account().await?.balance,
+        NearToken::from_near(1)
+    );
+
+    // The implicit account can sign with the key it was derived from.
+    let receiver = worker.dev_create_account().await?;
+    account
+        .transfer_near(receiver.id(), NearToken::from_millinear(100))
+        .await?
+        .into_result()?;
+
+    // Any account can fund an implicit account, not only the root account of sandbox.
+    let sk = SecretKey::from_random(KeyType::ED25519);
+    let funded = receiver
+        .create_implicit_account(sk.clone(), NearToken::from_millinear(500))
+        .await?
+        .into_result()?;
+    assert_eq!(funded.id(), &sk.implicit_account_id());
+    assert_eq!(
+        funded.view_account().await?.balance,
+        NearToken::from_millinear(500)
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_access_key_management() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let account = worker.dev_create_account().await?;
+    let receiver = worker.dev_create_account().await?;
+    let initial_keys = account.view_access_keys().await?.len();
+
+    let full_sk = SecretKey::from_random(KeyType::ED25519);
+    account
+        .add_full_access_key(&full_sk.public_key())
+        .await?
+        .into_result()?;
+    let fc_sk = SecretKey::from_random(KeyType::ED25519);
+    account
+        .add_function_call_key(
+            &fc_sk.public_key(),
+            receiver.id(),
+            &["some_method"],
+            Some(NearToken::from_near(1)),
+        )
+        .await?
+        .into_result()?;
+    assert_eq!(account.view_access_keys().await?.len(), initial_keys + 2);
+
+    // The added full access key can sign right away.
+    account
+        .with_key(full_sk.clone())
+        .transfer_near(receiver.id(), NearToken::from_millinear(1))
+        .await?
+        .into_result()?;
+
+    account
+        .delete_key(&fc_sk.public_key())
+        .await?
+        .into_result()?;
+    assert_eq!(account.view_access_keys().await?.len(), initial_keys + 1);
+
+    let generated = account
+        .generate_and_add_key(AccessKey::full_access())
+        .await?
+        .into_result()?;
+    assert_ne!(generated.secret_key(), account.secret_key());
+    generated
+        .transfer_near(receiver.id(), NearToken::from_millinear(1))
+        .await?
+        .into_result()?;
+
+    // Only accounts signing with a generated function call key report their allowance.
+    let generated_fc = account
+        .generate_and_add_key(AccessKey::function_call_access(
+            receiver.id(),
+            &["some_method"],
+            Some(NearToken::from_near(1)),
+        ))
+        .await?
+        .into_result()?;
+    let outcome = generated_fc
+        .call(receiver.id(), "some_method")
+        .transact()
+        .await?;
+    assert!(outcome.allowance_used().is_some());
+    let outcome = generated
+        .call(receiver.id(), "some_method")
+        .transact()
+        .await?;
+    assert!(outcome.allowance_used().is_none());
+
+    // Rotating replaces the signing key, so the old key can no longer be used.
+    let rotated = account.rotate_key().await?.into_result()?;
+    assert_ne!(rotated.secret_key(), account.secret_key());
+    rotated
+        .transfer_near(receiver.id(), NearToken::from_millinear(1))
+        .await?
+        .into_result()?;
+    assert!(account
+        .view_access_key(&account.secret_key().public_key())
+        .await
+        .is_err());
+    assert_eq!(account.view_access_keys().await?.len(), initial_keys + 3);
+
+    // No account is handed out for a key that could not be added.
+    assert!(account.rotate_key().await.is_err());
+    assert!(account
+        .generate_and_add_key(AccessKey::full_access())
+        .await
+        .is_err());
+    assert_eq!(account.view_access_keys().await?.len(), initial_keys + 3);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_cleanup_created_accounts() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let beneficiary = worker.root_account()?;
+
+    let account = worker.dev_create_account().await?;
+    let sub = account
+        .create_subaccount("sub")
+        .initial_balance(NearToken::from_near(1))
+        .transact()
+        .await?
+        .into_result()?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+
+    let created = worker.created_accounts()?;
+    assert_eq!(
+        created,
+        vec![
+            account.id().clone(),
+            sub.id().clone(),
+            contract.id().clone()
+        ]
+    );
+
+    let report = worker.cleanup(beneficiary.id()).await?;
+    assert!(report.is_success(), "{}", report);
+    assert_eq!(report.deleted.len(), 3);
+    assert!(worker.created_accounts()?.is_empty());
+    for id in &created {
+        assert!(worker.view_account(id).await.is_err());
+    }
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_mainnet_is_read_only() -> anyhow::Result<()> {
+    let worker = near_workspaces::mainnet().await?;
+    let account = near_workspaces::Account::from_secret_key(
+        "alice.near".parse()?,
+        SecretKey::from_random(KeyType::ED25519),
+        &worker,
+    );
+
+    // Refused before anything gets signed or sent, so the key does not need to exist.
+    let err = account
+        .transfer_near(account.id(), NearToken::from_yoctonear(1))
+        .await
+        .expect_err("mainnet should refuse transactions by default");
+    assert_eq!(err.kind(), &ErrorKind::ReadOnly);
+
+    // Queries still go through.
+    worker.view_block().await?;
+
+    Ok(())
+}
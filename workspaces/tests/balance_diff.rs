@@ -0,0 +1,64 @@
+use near_workspaces::error::ErrorKind;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_balance_diff() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let alice = worker.dev_create_account().await?;
+
+    let deposit = NearToken::from_millinear(100);
+    let outcome = alice
+        .call(contract.id(), "set_status")
+        .args_json(json!({ "message": "hello" }))
+        .deposit(deposit)
+        .with_balance_diff()
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    let diff = outcome.balance_diff().expect("balance diff was requested");
+    assert_eq!(&diff.accounts()[0].account_id, alice.id());
+
+    let alice_diff = diff.get(alice.id()).unwrap();
+    assert!(alice_diff.gas_cost > NearToken::from_yoctonear(0));
+    // Besides the gas, alice only paid for the deposit, give or take rounding of refunds.
+    let deposit_paid = -alice_diff.transfer_delta();
+    let tolerance = NearToken::from_millinear(1).as_yoctonear() as i128;
+    assert!((deposit_paid - deposit.as_yoctonear() as i128).abs() < tolerance);
+    assert_eq!(alice_diff.storage_usage_delta(), 0);
+
+    let contract_diff = diff.get(contract.id()).unwrap();
+    assert_eq!(contract_diff.gas_cost, NearToken::from_yoctonear(0));
+    assert!(contract_diff.storage_usage_delta() > 0);
+    // The contract receives the deposit along with its share of the gas rewards.
+    assert!(contract_diff.amount_delta() >= deposit.as_yoctonear() as i128);
+
+    assert!(diff.to_string().contains(alice.id().as_str()));
+
+    // Without opting in, no diff is recorded.
+    let outcome = alice
+        .call(contract.id(), "set_status")
+        .args_json(json!({ "message": "bye" }))
+        .transact()
+        .await?;
+    assert!(outcome.balance_diff().is_none());
+
+    // Balances cannot be recorded around a transaction that is not waited for.
+    let result = alice
+        .call(contract.id(), "set_status")
+        .args_json(json!({ "message": "bye" }))
+        .with_balance_diff()
+        .transact_async()
+        .await;
+    let Err(err) = result else {
+        panic!("balance diff should be rejected by transact_async");
+    };
+    assert_eq!(err.kind(), &ErrorKind::Usage);
+
+    Ok(())
+}
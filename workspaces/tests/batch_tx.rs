@@ -1,5 +1,6 @@
+use near_workspaces::error::ErrorKind;
 use near_workspaces::operations::Function;
-use near_workspaces::types::NearToken;
+use near_workspaces::types::{NearToken, TxExecutionStatus};
 use serde_json::json;
 use test_log::test;
 
@@ -40,3 +41,55 @@ async fn test_batch_tx() -> anyhow::Result<()> {
     assert_eq!(status_msg, "world_hello");
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_batch_tx_wait_until_final() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+
+    contract
+        .call("set_status")
+        .args_json(json!({
+            "message": "hello_final",
+        }))
+        .wait_until(TxExecutionStatus::Final)
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Inclusion-only levels carry no execution outcome, so `transact` rejects them.
+    let result = contract
+        .batch()
+        .call(Function::new("set_status").args_json(json!({
+            "message": "hello_included",
+        })))
+        .wait_until(TxExecutionStatus::Included)
+        .transact()
+        .await;
+    let err = result.unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Usage);
+    assert!(err.to_string().contains("transact_async"), "{}", err);
+
+    let status = contract
+        .call("set_status")
+        .args_json(json!({
+            "message": "hello_async",
+        }))
+        .wait_until(TxExecutionStatus::Final)
+        .transact_async()
+        .await?;
+    status.await?.into_result()?;
+
+    let status_msg: String = contract
+        .view("get_status")
+        .args_json(json!({
+            "account_id": contract.id(),
+        }))
+        .await?
+        .json()?;
+    assert_eq!(status_msg, "hello_async");
+
+    Ok(())
+}
@@ -2,8 +2,12 @@
 use serde::{Deserialize, Serialize};
 use test_log::test;
 
+use std::path::Path;
+
+use near_workspaces::error::ErrorKind;
 use near_workspaces::network::ValidatorKey;
-use near_workspaces::{pick_unused_port, DevNetwork, Worker};
+use near_workspaces::types::{ContractCode, NearToken};
+use near_workspaces::{pick_unused_port, DevNetwork, InMemorySigner, Worker};
 
 const NFT_WASM_FILEPATH: &str = "../examples/res/non_fungible_token.wasm";
 const EXPECTED_NFT_METADATA: &str = r#"{
@@ -107,6 +111,42 @@ async fn test_dev_create_account_testnet() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Requires the credentials file of a funded testnet account, passed through the
+/// `NEAR_WORKSPACES_TESTNET_SPONSOR` environment variable. Run with `--ignored`.
+#[test(tokio::test)]
+#[ignore = "requires NEAR_WORKSPACES_TESTNET_SPONSOR"]
+async fn test_dev_deploy_testnet_sponsor() -> anyhow::Result<()> {
+    let credentials = std::env::var("NEAR_WORKSPACES_TESTNET_SPONSOR")
+        .expect("NEAR_WORKSPACES_TESTNET_SPONSOR should point to a credentials file");
+    let sponsor = InMemorySigner::from_file(Path::new(&credentials))?;
+    let sponsor_id = sponsor.account_id().clone();
+
+    let worker = near_workspaces::testnet()
+        .sponsor(sponsor)
+        .sponsor_initial_balance(NearToken::from_near(5))
+        .await?;
+    assert_eq!(worker.root_account_id(), sponsor_id);
+
+    let account = worker.dev_create_account().await?;
+    assert!(account.id().is_sub_account_of(&sponsor_id));
+    // The sponsor pays for the creation, so the account holds exactly what it was given.
+    assert_eq!(
+        account.view_account().await?.balance,
+        NearToken::from_near(5)
+    );
+    deploy_and_assert(worker).await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_testnet_sponsor_initial_balance_requires_sponsor() -> anyhow::Result<()> {
+    let result = near_workspaces::testnet()
+        .sponsor_initial_balance(NearToken::from_near(5))
+        .await;
+    assert!(matches!(result, Err(err) if err.kind() == &ErrorKind::Usage));
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_manually_spawned_deploy() -> anyhow::Result<()> {
     let rpc_port = pick_unused_port().await?;
@@ -134,3 +174,81 @@ async fn test_manually_spawned_deploy() -> anyhow::Result<()> {
     child.kill().await?;
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_contract_code_validation() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(NFT_WASM_FILEPATH)?;
+
+    let max_contract_size = worker.max_contract_size().await?;
+    assert!(max_contract_size > 0);
+
+    let code = ContractCode::parse(wasm.clone())?;
+    code.validate(max_contract_size)?;
+    assert!(code.has_export("nft_metadata"));
+    assert!(code.unknown_imports().is_empty());
+    assert!(code.size() as u64 <= max_contract_size);
+
+    let err = code.validate(code.size() as u64 - 1).unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Wasm);
+
+    // Host functions unknown to the library are told apart from imports of other modules,
+    // which can never be provided.
+    let code = ContractCode::parse(wasm_importing(&[
+        ("env", "promise_batch_action_deploy_global_contract"),
+        ("env", "not_a_host_fn"),
+        ("other", "function"),
+    ]))?;
+    let unknown = code.unknown_imports();
+    assert_eq!(unknown.len(), 2);
+    assert_eq!(unknown[0].to_string(), "env.not_a_host_fn");
+    assert_eq!(unknown[1].to_string(), "other.function");
+    let err = code.validate(max_contract_size).unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Wasm);
+    let message = err.to_string();
+    assert!(message.contains("from `env`: env.not_a_host_fn"));
+    assert!(message.contains("other than `env`: other.function"));
+
+    // Host functions of newer protocol versions can be accepted explicitly.
+    let code = ContractCode::parse(wasm_importing(&[("env", "not_a_host_fn")]))?
+        .with_host_functions(vec!["not_a_host_fn"]);
+    assert!(code.unknown_imports().is_empty());
+
+    let contract = worker.dev_deploy_checked(&wasm).await?;
+    assert_eq!(code, contract.view_code().await?);
+    assert_eq!(code, contract.view_account().await?.code_hash);
+
+    // Not wasm at all, so refused before any account gets created.
+    let err = worker
+        .dev_deploy_checked(b"definitely not wasm")
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Wasm);
+
+    Ok(())
+}
+
+/// A wasm module importing the given functions, all taking and returning nothing.
+fn wasm_importing(imports: &[(&str, &str)]) -> Vec<u8> {
+    fn name(name: &str) -> Vec<u8> {
+        let mut bytes = vec![name.len() as u8];
+        bytes.extend(name.as_bytes());
+        bytes
+    }
+
+    let mut import_section = vec![imports.len() as u8];
+    for (module, function) in imports {
+        import_section.extend(name(module));
+        import_section.extend(name(function));
+        // A function of the first type.
+        import_section.extend([0x00, 0x00]);
+    }
+
+    let mut wasm = b"\0asm".to_vec();
+    wasm.extend([0x01, 0x00, 0x00, 0x00]);
+    // Type section, with a single `() -> ()` function type.
+    wasm.extend([0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+    wasm.extend([0x02, import_section.len() as u8]);
+    wasm.extend(import_section);
+    wasm
+}
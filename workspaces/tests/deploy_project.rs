@@ -1,5 +1,8 @@
 #![cfg(feature = "unstable")]
 #![recursion_limit = "256"]
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
 use test_log::test;
 
 #[test(tokio::test)]
@@ -25,3 +28,39 @@ async fn test_dev_deploy_project() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_compile_options_cache() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let options = near_workspaces::CompileOptions::new("./tests/test-contracts/status-message");
+    let compiled = options.clone().compile().await?;
+    let cache_dir = Path::new("./tests/test-contracts/status-message/target/near-workspaces");
+    let cached_builds = cached_wasm_files(cache_dir)?;
+    assert!(!cached_builds.is_empty());
+
+    // Same sources and options, so this comes out of the cache instead of rewriting it.
+    let cached = options.compile().await?;
+    assert_eq!(compiled.wasm, cached.wasm);
+    assert_eq!(compiled.code_hash, cached.code_hash);
+    assert_eq!(cached_wasm_files(cache_dir)?, cached_builds);
+
+    let contract = worker.dev_deploy(&compiled.wasm).await?;
+    assert_eq!(contract.view_account().await?.code_hash, compiled.code_hash);
+
+    Ok(())
+}
+
+/// Every wasm file in the compilation cache, along with when it was last written.
+fn cached_wasm_files(dir: &Path) -> anyhow::Result<Vec<(PathBuf, SystemTime)>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(cached_wasm_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "wasm") {
+            files.push((path.clone(), std::fs::metadata(&path)?.modified()?));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
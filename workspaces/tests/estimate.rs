@@ -0,0 +1,118 @@
+use near_workspaces::types::NearToken;
+use near_workspaces::AccountId;
+use serde_json::json;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_estimate_call_on_sandbox() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let balance_before = contract.view_account().await?.balance;
+
+    let estimate = contract
+        .call("set_status")
+        .args_json(json!({ "message": "hello" }))
+        .estimate()
+        .await?;
+
+    assert!(estimate.is_success());
+    assert!(estimate.total_gas_burnt.as_gas() > 0);
+    assert!(estimate.tokens_burnt > NearToken::from_yoctonear(0));
+    assert_eq!(estimate.outcomes.len(), estimate.result().outcomes().len());
+    assert_eq!(
+        estimate
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.gas_burnt.as_gas())
+            .sum::<u64>(),
+        estimate.total_gas_burnt.as_gas()
+    );
+
+    // The dry-run should have been reverted.
+    let status: Option<String> = contract
+        .view("get_status")
+        .args_json(json!({ "account_id": contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status, None);
+    assert_eq!(contract.view_account().await?.balance, balance_before);
+
+    // Transactions can still be sent with the signer's key after the dry-run.
+    contract
+        .call("set_status")
+        .args_json(json!({ "message": "hello" }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Reverting brings back the state the accounts had right before the dry-run.
+    let estimate = contract
+        .call("set_status")
+        .args_json(json!({ "message": "world" }))
+        .estimate()
+        .await?;
+    assert!(estimate.is_success());
+    let status: Option<String> = contract
+        .view("get_status")
+        .args_json(json!({ "account_id": contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status.as_deref(), Some("hello"));
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_estimate_cross_contract_call_on_sandbox() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let factory = worker
+        .dev_deploy(include_bytes!(
+            "../../examples/res/factory_contract_high_level.wasm"
+        ))
+        .await?;
+
+    // Accounts created by the dry-run should be deleted afterwards.
+    let status_id: AccountId = format!("status.{}", factory.id()).parse()?;
+    let estimate = factory
+        .call("deploy_status_message")
+        .args_json((status_id.clone(), NearToken::from_near(35)))
+        .deposit(NearToken::from_near(50))
+        .max_gas()
+        .estimate()
+        .await?;
+    assert!(estimate.is_success());
+    assert!(estimate
+        .outcomes
+        .iter()
+        .any(|outcome| outcome.executor_id == status_id));
+    assert!(worker.view_account(&status_id).await.is_err());
+
+    factory
+        .call("deploy_status_message")
+        .args_json((status_id.clone(), NearToken::from_near(35)))
+        .deposit(NearToken::from_near(50))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Accounts reached through cross-contract calls should be reverted as well.
+    let estimate = factory
+        .call("complex_call")
+        .args_json((status_id.clone(), "hello"))
+        .max_gas()
+        .estimate()
+        .await?;
+    assert!(estimate.is_success());
+    assert_eq!(estimate.result().clone().json::<String>()?, "hello");
+    let status: Option<String> = worker
+        .view(&status_id, "get_status")
+        .args_json(json!({ "account_id": factory.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status, None);
+
+    Ok(())
+}
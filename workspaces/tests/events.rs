@@ -0,0 +1,76 @@
+#![cfg(feature = "unstable")]
+use near_workspaces::result::{FtTransfer, Nep141Event, Nep171Event, NftMint};
+use serde_json::json;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_events() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = near_workspaces::compile_project("./tests/test-contracts/events").await?;
+    let contract = worker.dev_deploy(&wasm).await?;
+    let alice = worker.dev_create_account().await?;
+
+    let outcome = alice
+        .call(contract.id(), "emit_ft_transfer")
+        .args_json(json!({ "receiver_id": contract.id(), "amount": "1000" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    // Plain logs are not events.
+    assert_eq!(outcome.logs().len(), 2);
+    let events = outcome.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].standard, "nep141");
+    assert_eq!(events[0].event, "ft_transfer");
+    assert_eq!(&events[0].executor_id, contract.id());
+
+    let expected = FtTransfer {
+        old_owner_id: alice.id().clone(),
+        new_owner_id: contract.id().clone(),
+        amount: 1000,
+        memo: None,
+    };
+    let transfers: Vec<Vec<FtTransfer>> = outcome.events_of("nep141", "ft_transfer")?;
+    assert_eq!(transfers, vec![vec![expected.clone()]]);
+    assert_eq!(
+        outcome.typed_events::<Nep141Event>()?,
+        vec![Nep141Event::FtTransfer(vec![expected])]
+    );
+    assert!(outcome.typed_events::<Nep171Event>()?.is_empty());
+
+    // Events are also available from the outcome that emitted them.
+    let receipt = &outcome.receipt_outcomes()[0];
+    assert_eq!(receipt.events(), events);
+
+    let outcome = contract
+        .call("emit_nft_mint")
+        .args_json(json!({ "owner_id": alice.id(), "token_ids": ["0", "1"] }))
+        .transact()
+        .await?
+        .into_result()?;
+    let expected = NftMint {
+        owner_id: alice.id().clone(),
+        token_ids: vec!["0".to_string(), "1".to_string()],
+        memo: None,
+    };
+    // Events are still available once the outcome is turned into an `ExecutionSuccess`.
+    let mints: Vec<Vec<NftMint>> = outcome.events_of("nep171", "nft_mint")?;
+    assert_eq!(mints, vec![vec![expected.clone()]]);
+    assert_eq!(
+        outcome.typed_events::<Nep171Event>()?,
+        vec![Nep171Event::NftMint(vec![expected])]
+    );
+
+    // Event types unknown to the standard enums are skipped.
+    let outcome = contract
+        .call("emit_event")
+        .args_json(json!({ "standard": "nep171", "version": "9.0.0", "event": "nft_frobnicate" }))
+        .transact()
+        .await?
+        .into_result()?;
+    assert_eq!(outcome.events().len(), 1);
+    assert!(outcome.typed_events::<Nep171Event>()?.is_empty());
+
+    Ok(())
+}
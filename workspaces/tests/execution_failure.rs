@@ -0,0 +1,145 @@
+use std::panic::AssertUnwindSafe;
+
+use near_primitives::errors::{CompilationError, FunctionCallError};
+use near_workspaces::result::ExecutionFailureKind;
+use near_workspaces::types::{Gas, NearToken};
+use serde_json::json;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_execution_failure_kinds() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let ft = worker
+        .dev_deploy(include_bytes!("../../examples/res/fungible_token.wasm"))
+        .await?;
+    ft.call("new_default_meta")
+        .args_json(json!({
+            "owner_id": ft.id(),
+            "total_supply": NearToken::from_near(1_000),
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Transferring tokens requires exactly 1 yoctoNEAR to be attached.
+    let failure = ft
+        .call("ft_transfer")
+        .args_json(json!({ "receiver_id": worker.root_account()?.id(), "amount": "1" }))
+        .transact()
+        .await?
+        .into_result()
+        .unwrap_err();
+    let panic_message = failure.panic_message().expect("should have panicked");
+    assert!(panic_message.contains("1 yoctoNEAR"));
+    assert!(matches!(
+        failure.failure_kind(),
+        ExecutionFailureKind::InsufficientDeposit(_)
+    ));
+    assert!(failure.action_error_kind().is_some());
+
+    let receipt = failure
+        .failed_receipt()
+        .expect("should have a failed receipt");
+    assert_eq!(receipt.executor_id, *ft.id());
+    assert_eq!(receipt.panic_message(), Some(panic_message));
+
+    let failure = ft
+        .call("not_a_method")
+        .transact()
+        .await?
+        .into_result()
+        .unwrap_err();
+    assert_eq!(failure.failure_kind(), ExecutionFailureKind::MethodNotFound);
+    assert_eq!(failure.panic_message(), None);
+
+    let account = worker.dev_create_account().await?;
+    let failure = account
+        .call(account.id(), "anything")
+        .transact()
+        .await?
+        .into_result()
+        .unwrap_err();
+    assert_eq!(
+        failure.failure_kind(),
+        ExecutionFailureKind::ContractNotDeployed(account.id().clone())
+    );
+
+    // Other function call errors are kept as is, rather than as their message.
+    let broken = worker.dev_deploy(b"not a wasm module").await?;
+    let failure = broken
+        .call("anything")
+        .transact()
+        .await?
+        .into_result()
+        .unwrap_err();
+    assert!(matches!(
+        failure.failure_kind(),
+        ExecutionFailureKind::FunctionCall(FunctionCallError::CompilationError(
+            CompilationError::PrepareError(_)
+        ))
+    ));
+    assert_eq!(failure.failed_receipt().unwrap().executor_id, *broken.id());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_execution_assertions() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+
+    let outcome = contract
+        .call("set_status")
+        .args_json(json!({ "message": "hello" }))
+        .transact()
+        .await?;
+    outcome
+        .assert_success()
+        .assert_log("set_status with message hello")
+        // The function call, and the refund of the gas it did not use.
+        .assert_receipt_count(2)
+        .assert_gas_below(Gas::from_tgas(300));
+    outcome.clone().into_result()?.assert_log("hello");
+
+    let outcome = contract.call("not_a_method").transact().await?;
+    outcome.assert_failure_contains("MethodNotFound");
+    outcome
+        .into_result()
+        .unwrap_err()
+        .assert_failure_contains("MethodNotFound");
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_failed_assertion_reports_receipts() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+
+    let outcome = contract
+        .call("set_status")
+        .args_json(json!({ "message": "hello" }))
+        .transact()
+        .await?;
+    let panic = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        outcome.assert_log("goodbye");
+    }))
+    .expect_err("assertion should have failed");
+    let msg = panic
+        .downcast_ref::<String>()
+        .expect("panic message should be formatted");
+
+    assert!(msg.starts_with("expected a log containing `goodbye`"));
+    assert!(msg.contains("receipts:\nTransaction "));
+    assert!(msg.contains("FunctionCall(set_status)"));
+    assert!(msg.contains(&format!(
+        "logs:\n  [{}] set_status with message hello",
+        contract.id()
+    )));
+
+    Ok(())
+}
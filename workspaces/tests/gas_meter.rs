@@ -325,3 +325,63 @@ async fn test_dropped_gas_meter() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_gas_meter_report() -> anyhow::Result<()> {
+    let mut worker = near_workspaces::sandbox().await?;
+    let gas_meter = GasMeter::now(&mut worker);
+
+    let status_msg = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let account = worker.dev_create_account().await?;
+
+    let mut set_status_gas = 0;
+    for message in ["hello", "world"] {
+        let txn = account
+            .call(status_msg.id(), "set_status")
+            .args_json(json!({ "message": message }))
+            .transact()
+            .await?;
+        set_status_gas += txn.total_gas_burnt.as_gas();
+    }
+
+    let report = gas_meter.report()?;
+    assert_eq!(report.transactions, 4);
+    assert_eq!(report.total_gas, gas_meter.elapsed()?);
+    assert_eq!(report.tokens_burnt, gas_meter.tokens_burnt()?);
+    assert!(report.tokens_burnt > NearToken::from_yoctonear(0));
+
+    let set_status = report
+        .get(status_msg.id(), Some("set_status"))
+        .expect("set_status calls should be reported");
+    assert_eq!(set_status.transactions, 2);
+    assert_eq!(set_status.gas.as_gas(), set_status_gas);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()?)?;
+    assert_eq!(json["transactions"], 4);
+    assert!(report.to_markdown().contains("| set_status | 2 |"));
+
+    gas_meter.reset()?;
+    assert_eq!(gas_meter.report()?.transactions, 0);
+    assert!(gas_meter.report()?.calls.is_empty());
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_gas_meter_deregister() -> anyhow::Result<()> {
+    let mut worker = near_workspaces::sandbox().await?;
+    let gas_meter = GasMeter::now(&mut worker);
+
+    worker.dev_create_account().await?;
+    let elapsed = gas_meter.elapsed()?;
+    assert_eq!(gas_meter.transactions()?, 1);
+
+    gas_meter.deregister(&mut worker);
+    worker.dev_create_account().await?;
+    assert_eq!(gas_meter.elapsed()?, elapsed);
+    assert_eq!(gas_meter.transactions()?, 1);
+
+    Ok(())
+}
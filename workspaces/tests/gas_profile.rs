@@ -0,0 +1,36 @@
+use serde_json::json;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_gas_profile() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+
+    let outcome = contract
+        .call("set_status")
+        .args_json(json!({ "message": "hello" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    // The function call receipt reports where its gas went.
+    let receipt = &outcome.receipt_outcomes()[0];
+    let receipt_profile = receipt.gas_profile.as_ref().expect("missing gas profile");
+    assert!(receipt_profile.get("WASM_INSTRUCTION").as_gas() > 0);
+    assert!(receipt_profile.total() <= receipt.gas_burnt);
+
+    let profile = outcome.gas_profile();
+    assert!(!profile.is_empty());
+    assert!(profile.get("WASM_INSTRUCTION") >= receipt_profile.get("WASM_INSTRUCTION"));
+    assert!(profile.by_category().contains_key("WASM_HOST_COST"));
+    assert!(profile.total() <= outcome.total_gas_burnt);
+
+    // Entries are sorted from the most expensive cost.
+    let entries = profile.entries();
+    assert!(entries.windows(2).all(|w| w[0].gas_used >= w[1].gas_used));
+    assert!(profile.to_string().contains("TOTAL"));
+
+    Ok(())
+}
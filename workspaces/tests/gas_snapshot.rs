@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use test_log::test;
+
+use near_workspaces::error::ErrorKind;
+use near_workspaces::types::{Gas, GasSnapshot, GasSnapshotStatus};
+
+fn baseline_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "near-workspaces-gas-{name}-{}.json",
+        std::process::id()
+    ))
+}
+
+#[test]
+fn test_gas_snapshot_against_baseline() -> anyhow::Result<()> {
+    let path = baseline_path("baseline");
+    std::fs::write(
+        &path,
+        serde_json::to_string(&json!({
+            "unchanged": 1000,
+            "regressed": 1000,
+            "improved": 1000,
+            "lenient": 1000,
+            "untouched": 1000,
+        }))?,
+    )?;
+
+    let mut snapshot = GasSnapshot::new(&path)
+        .tolerance(5.0)
+        .tolerance_for("lenient", 50.0);
+    snapshot
+        .record("unchanged", Gas::from_gas(1040))
+        .record("regressed", Gas::from_gas(1100))
+        .record("improved", Gas::from_gas(900))
+        .record("lenient", Gas::from_gas(1400));
+
+    let diff = snapshot.compare()?;
+    let status = |name: &str| {
+        diff.entries()
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.status())
+    };
+    assert_eq!(status("unchanged"), Some(GasSnapshotStatus::Unchanged));
+    assert_eq!(status("regressed"), Some(GasSnapshotStatus::Regressed));
+    assert_eq!(status("improved"), Some(GasSnapshotStatus::Improved));
+    assert_eq!(status("lenient"), Some(GasSnapshotStatus::Unchanged));
+    assert_eq!(status("untouched"), None);
+    assert_eq!(diff.regressions().len(), 1);
+    assert_eq!(diff.improvements().len(), 1);
+
+    let table = diff.to_string();
+    assert!(table.contains("regressed") && table.contains("+10.00%"));
+    assert!(!table.contains("unchanged"));
+
+    let err = snapshot.check().unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::GasRegression);
+
+    // Updating keeps the entries that were not recorded.
+    snapshot.update()?;
+    let baseline: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    assert_eq!(baseline["regressed"], 1100);
+    assert_eq!(baseline["untouched"], 1000);
+    assert!(!snapshot.compare()?.has_regressions());
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_gas_snapshot_new_measurement() -> anyhow::Result<()> {
+    let path = baseline_path("new");
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+
+    let txn = contract
+        .call("set_status")
+        .args_json(json!({ "message": "hello" }))
+        .transact()
+        .await?;
+
+    let mut snapshot = GasSnapshot::new(&path);
+    snapshot.record_result("set_status", &txn);
+
+    // Measurements without a baseline fail the check until the baseline is updated.
+    let diff = snapshot.compare()?;
+    assert_eq!(diff.new_entries().len(), 1);
+    assert!(diff.has_new_entries());
+    assert!(!diff.has_regressions());
+    assert!(snapshot.check().is_err());
+
+    snapshot.update()?;
+    let diff = snapshot.check()?;
+    assert_eq!(diff.entries()[0].baseline, Some(txn.total_gas_burnt));
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
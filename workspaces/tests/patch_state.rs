@@ -6,7 +6,8 @@ use near_token::NearToken;
 use serde_json::json;
 use test_log::test;
 
-use near_workspaces::types::{KeyType, SecretKey};
+use near_workspaces::error::ErrorKind;
+use near_workspaces::types::{ContractStateExt, KeyType, SecretKey};
 use near_workspaces::{AccessKey, AccountDetailsPatch, AccountId, Contract, DevNetwork, Worker};
 
 const STATUS_MSG_WASM_FILEPATH: &str = "../examples/res/status_message.wasm";
@@ -66,6 +67,133 @@ async fn test_view_state() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_view_state_decode_missing_key() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(&std::fs::read(STATUS_MSG_WASM_FILEPATH)?)
+        .await?;
+
+    // Nothing has been stored yet, not even the contract struct.
+    let err = contract
+        .view_state()
+        .await?
+        .decode::<StatusMessage>(b"STATE")
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::DataConversion);
+
+    Ok(())
+}
+
+#[cfg(feature = "interop_sdk")]
+#[test(tokio::test)]
+async fn test_view_state_lookup_map() -> anyhow::Result<()> {
+    const FT_WASM_FILEPATH: &str = "../examples/res/fungible_token.wasm";
+    const TOTAL_SUPPLY: u128 = 1_000_000;
+
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(&std::fs::read(FT_WASM_FILEPATH)?).await?;
+    contract
+        .call("new_default_meta")
+        .args_json(json!({
+            "owner_id": contract.id(),
+            "total_supply": TOTAL_SUPPLY.to_string(),
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Balances are kept in a `LookupMap` under the first variant of the `StorageKey` enum
+    // of the contract, which is Borsh encoded as a single zero byte.
+    let state = contract.view_state().await?;
+    let balances: Vec<(AccountId, u128)> = state.lookup_map(vec![0])?;
+    assert_eq!(balances, [(contract.id().clone(), TOTAL_SUPPLY)]);
+
+    Ok(())
+}
+
+/// Deploy the collections fixture, and fill its collections with the keys `a`, `b` and `c`,
+/// before removing `a` from its maps and swap removing the first element of its vector.
+#[cfg(all(feature = "interop_sdk", feature = "unstable"))]
+async fn collections_contract(
+) -> anyhow::Result<(Contract, std::collections::HashMap<Vec<u8>, Vec<u8>>)> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = near_workspaces::compile_project("./tests/test-contracts/collections").await?;
+    let contract = worker.dev_deploy(&wasm).await?;
+    contract.call("new").transact().await?.into_result()?;
+
+    for (value, key) in ["a", "b", "c"].iter().enumerate() {
+        contract
+            .call("insert")
+            .args_json(json!({ "key": key, "value": value }))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+    contract
+        .call("remove")
+        .args_json(json!({ "key": "a" }))
+        .transact()
+        .await?
+        .into_result()?;
+    contract
+        .call("swap_remove")
+        .args_json(json!({ "index": 0 }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let state = contract.view_state().await?;
+    Ok((contract, state))
+}
+
+// The collections of the fixture are prefixed by the variants of its `StorageKey` enum,
+// Borsh encoded as a single byte each.
+
+#[cfg(all(feature = "interop_sdk", feature = "unstable"))]
+#[test(tokio::test)]
+async fn test_view_state_iterable_map() -> anyhow::Result<()> {
+    let (contract, state) = collections_contract().await?;
+
+    // Removing `a` moved the last key into its place.
+    let entries: Vec<(String, u64)> = state.iterable_map(vec![1])?;
+    assert_eq!(entries, [("c".to_string(), 2), ("b".to_string(), 1)]);
+    let iterated: Vec<(String, u64)> = contract.view("iterable_map").await?.json()?;
+    assert_eq!(entries, iterated);
+
+    let entries: Vec<(String, u64)> = state.lookup_map(vec![0])?;
+    assert_eq!(entries, [("b".to_string(), 1), ("c".to_string(), 2)]);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "interop_sdk", feature = "unstable"))]
+#[test(tokio::test)]
+async fn test_view_state_unordered_map() -> anyhow::Result<()> {
+    let (contract, state) = collections_contract().await?;
+
+    // Removing `a` left a hole in place of it, rather than moving the other keys.
+    let entries: Vec<(String, u64)> = state.unordered_map(vec![2])?;
+    assert_eq!(entries, [("b".to_string(), 1), ("c".to_string(), 2)]);
+    let iterated: Vec<(String, u64)> = contract.view("unordered_map").await?.json()?;
+    assert_eq!(entries, iterated);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "interop_sdk", feature = "unstable"))]
+#[test(tokio::test)]
+async fn test_view_state_vector() -> anyhow::Result<()> {
+    let (contract, state) = collections_contract().await?;
+
+    let elements: Vec<String> = state.vector(vec![3])?;
+    assert_eq!(elements, ["c", "b"]);
+    let iterated: Vec<String> = contract.view("vector").await?.json()?;
+    assert_eq!(elements, iterated);
+
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_patch_state() -> anyhow::Result<()> {
     let worker = near_workspaces::sandbox().await?;
@@ -0,0 +1,62 @@
+use near_primitives::views::ActionView;
+use serde_json::json;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_receipt_tree() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(include_bytes!("../../examples/res/status_message.wasm"))
+        .await?;
+    let account = worker.dev_create_account().await?;
+
+    let outcome = account
+        .call(contract.id(), "set_status")
+        .args_json(json!({ "message": "hello" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    let tree = outcome.receipt_tree();
+    assert_eq!(tree.len(), outcome.outcomes().len());
+
+    let root = tree.root();
+    assert!(root.is_transaction());
+    assert_eq!(&root.executor_id, account.id());
+    assert_eq!(&root.receiver_id, contract.id());
+    assert_eq!(root.actions.as_ref().map(Vec::len), Some(1));
+
+    let call = tree.children(root)[0];
+    assert_eq!(tree.parent(call), Some(root));
+    assert_eq!(&call.executor_id, contract.id());
+    assert_eq!(call.predecessor_id.as_ref(), Some(account.id()));
+    assert_eq!(call.actions, root.actions);
+    assert!(call.is_success());
+
+    // Gas refunds are spawned by the protocol rather than by the account executing the
+    // receipt they refund.
+    let refunds: Vec<_> = tree
+        .children(call)
+        .into_iter()
+        .filter(|node| node.predecessor_id.as_ref().map(AsRef::as_ref) == Some("system"))
+        .collect();
+    assert!(!refunds.is_empty());
+    for refund in &refunds {
+        assert_eq!(&refund.executor_id, account.id());
+        assert!(matches!(
+            refund.actions.as_deref(),
+            Some([ActionView::Transfer { .. }])
+        ));
+    }
+
+    let nodes = tree.nodes();
+    assert_eq!(nodes.len(), tree.len());
+    assert_eq!(nodes[0], root);
+
+    let printed = tree.to_string();
+    assert!(printed.starts_with("Transaction "));
+    assert!(printed.contains("FunctionCall(set_status)"));
+    assert!(printed.contains("[SUCCESS]"));
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+#![allow(deprecated)]
+
+use near_sdk::store::{IterableMap, LookupMap, UnorderedMap, Vector};
+use near_sdk::{near, BorshStorageKey, PanicOnDefault};
+
+/// Prefixes of the collections, Borsh encoded as a single byte each: `0` for the
+/// `LookupMap`, `1` for the `IterableMap`, and so on.
+#[near(serializers = [borsh])]
+#[derive(BorshStorageKey)]
+enum StorageKey {
+    LookupMap,
+    IterableMap,
+    UnorderedMap,
+    Vector,
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Collections {
+    lookup_map: LookupMap<String, u64>,
+    iterable_map: IterableMap<String, u64>,
+    unordered_map: UnorderedMap<String, u64>,
+    vector: Vector<String>,
+}
+
+#[near]
+impl Collections {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            lookup_map: LookupMap::new(StorageKey::LookupMap),
+            iterable_map: IterableMap::new(StorageKey::IterableMap),
+            unordered_map: UnorderedMap::new(StorageKey::UnorderedMap),
+            vector: Vector::new(StorageKey::Vector),
+        }
+    }
+
+    /// Inserts `key` into every map, and pushes it onto the vector.
+    pub fn insert(&mut self, key: String, value: u64) {
+        self.lookup_map.insert(key.clone(), value);
+        self.iterable_map.insert(key.clone(), value);
+        self.unordered_map.insert(key.clone(), value);
+        self.vector.push(key);
+    }
+
+    /// Removes `key` from every map, which reorders the keys of the `IterableMap` and leaves
+    /// a hole in those of the `UnorderedMap`.
+    pub fn remove(&mut self, key: String) {
+        self.lookup_map.remove(&key);
+        self.iterable_map.remove(&key);
+        self.unordered_map.remove(&key);
+    }
+
+    /// Removes the element at `index` from the vector, replacing it with the last one.
+    pub fn swap_remove(&mut self, index: u32) -> String {
+        self.vector.swap_remove(index)
+    }
+
+    pub fn iterable_map(&self) -> Vec<(&String, &u64)> {
+        self.iterable_map.iter().collect()
+    }
+
+    pub fn unordered_map(&self) -> Vec<(&String, &u64)> {
+        self.unordered_map.iter().collect()
+    }
+
+    pub fn vector(&self) -> Vec<&String> {
+        self.vector.iter().collect()
+    }
+}
@@ -0,0 +1,53 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde_json::json;
+use near_sdk::{env, log, near_bindgen, AccountId};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct Events {}
+
+#[near_bindgen]
+impl Events {
+    /// Emits a NEP-141 `ft_transfer` event from the predecessor to `receiver_id`,
+    /// along with a plain log that is not an event.
+    pub fn emit_ft_transfer(&mut self, receiver_id: AccountId, amount: String) {
+        log!("transferring {} to {}", amount, receiver_id);
+        let event = json!({
+            "standard": "nep141",
+            "version": "1.0.0",
+            "event": "ft_transfer",
+            "data": [{
+                "old_owner_id": env::predecessor_account_id(),
+                "new_owner_id": receiver_id,
+                "amount": amount,
+            }],
+        });
+        log!("EVENT_JSON:{}", event);
+    }
+
+    /// Emits a NEP-171 `nft_mint` event for `token_ids` owned by `owner_id`.
+    pub fn emit_nft_mint(&mut self, owner_id: AccountId, token_ids: Vec<String>) {
+        let event = json!({
+            "standard": "nep171",
+            "version": "1.2.0",
+            "event": "nft_mint",
+            "data": [{
+                "owner_id": owner_id,
+                "token_ids": token_ids,
+            }],
+        });
+        log!("EVENT_JSON:{}", event);
+    }
+
+    /// Emits an event of type `event` from the standard `standard`, without any data.
+    pub fn emit_event(&mut self, standard: String, version: String, event: String) {
+        let event = json!({
+            "standard": standard,
+            "version": version,
+            "event": event,
+            "data": [],
+        });
+        log!("EVENT_JSON:{}", event);
+    }
+}
@@ -0,0 +1,189 @@
+use near_workspaces::error::ErrorKind;
+use near_workspaces::result::ExecutionFailureKind;
+use near_workspaces::tokens::{FungibleToken, NonFungibleToken, TokenMetadata};
+use near_workspaces::types::NearToken;
+use near_workspaces::Contract;
+use serde_json::json;
+use test_log::test;
+
+const FT_WASM_FILEPATH: &str = "../examples/res/fungible_token.wasm";
+const NFT_WASM_FILEPATH: &str = "../examples/res/non_fungible_token.wasm";
+const STATUS_MSG_WASM_FILEPATH: &str = "../examples/res/status_message.wasm";
+const TOTAL_SUPPLY: u128 = 1_000_000;
+
+#[test(tokio::test)]
+async fn test_fungible_token() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(&std::fs::read(FT_WASM_FILEPATH)?).await?;
+    contract
+        .call("new_default_meta")
+        .args_json(json!({
+            "owner_id": contract.id(),
+            "total_supply": TOTAL_SUPPLY.to_string(),
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let ft = FungibleToken::new(contract);
+    let owner = ft.as_contract().as_account().clone();
+    let alice = worker.dev_create_account().await?;
+
+    assert_eq!(ft.ft_metadata().await?.decimals, 24);
+    assert_eq!(ft.ft_total_supply().await?, TOTAL_SUPPLY);
+    assert_eq!(ft.ft_balance_of(owner.id()).await?, TOTAL_SUPPLY);
+
+    // Alice has to be registered before receiving any tokens.
+    let err = ft
+        .ft_transfer(&owner, alice.id(), 100, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::Execution);
+
+    ft.ensure_registered(&alice, alice.id())
+        .await?
+        .expect("alice was not registered")
+        .into_result()?;
+    assert!(ft.is_registered(alice.id()).await?);
+    assert!(ft.ensure_registered(&alice, alice.id()).await?.is_none());
+    let storage = ft.storage_balance_of(alice.id()).await?.unwrap();
+    assert!(storage.total > NearToken::from_yoctonear(0));
+
+    ft.ft_transfer(&owner, alice.id(), 100, Some("hello"))
+        .await?
+        .into_result()?;
+    assert_eq!(ft.ft_balance_of(alice.id()).await?, 100);
+    assert_eq!(ft.ft_balance_of(owner.id()).await?, TOTAL_SUPPLY - 100);
+
+    // Alice has no contract to handle `ft_on_transfer`, so everything gets refunded.
+    let result = ft
+        .ft_transfer_call(&owner, alice.id(), 50, None, "take it")
+        .await?;
+    assert!(result.is_success());
+    assert_eq!(result.used_amount()?, 0);
+    assert_eq!(result.refunded_amount()?, 50);
+    assert_eq!(ft.ft_balance_of(alice.id()).await?, 100);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_fungible_token_registration_check() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let alice = worker.dev_create_account().await?;
+
+    // Contracts without `storage_balance_of` do not require registration, so the transfer
+    // goes through to the contract.
+    let contract = worker
+        .dev_deploy(&std::fs::read(STATUS_MSG_WASM_FILEPATH)?)
+        .await?;
+    let ft = FungibleToken::new(contract.clone());
+    let failure = ft
+        .ft_transfer(contract.as_account(), alice.id(), 100, None)
+        .await?
+        .into_result()
+        .unwrap_err();
+    assert_eq!(failure.failure_kind(), ExecutionFailureKind::MethodNotFound);
+
+    // Any other error checking the registration is not let through.
+    let account = worker.dev_create_account().await?;
+    let ft = FungibleToken::new(Contract::from_secret_key(
+        account.id().clone(),
+        account.secret_key().clone(),
+        &worker,
+    ));
+    let err = ft
+        .ft_transfer(&account, alice.id(), 100, None)
+        .await
+        .unwrap_err();
+    assert_ne!(err.kind(), &ErrorKind::Execution);
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_non_fungible_token() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker
+        .dev_deploy(&std::fs::read(NFT_WASM_FILEPATH)?)
+        .await?;
+    contract
+        .call("new_default_meta")
+        .args_json(json!({ "owner_id": contract.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let nft = NonFungibleToken::new(contract);
+    let owner = nft.as_contract().as_account().clone();
+    let alice = worker.dev_create_account().await?;
+    let bob = worker.dev_create_account().await?;
+
+    assert_eq!(nft.nft_metadata().await?.spec, "nft-1.0.0");
+    assert!(nft.nft_token("0").await?.is_none());
+
+    let metadata = TokenMetadata {
+        title: Some("Olympus Mons".into()),
+        copies: Some(1),
+        ..TokenMetadata::default()
+    };
+    let minted = nft
+        .nft_mint(&owner, "0", owner.id(), &metadata)
+        .await?
+        .into_result()?;
+    let mints = minted.mints()?;
+    assert_eq!(mints.len(), 1);
+    assert_eq!(&mints[0].owner_id, owner.id());
+    assert_eq!(mints[0].token_ids, ["0"]);
+
+    let token = nft.nft_token("0").await?.unwrap();
+    assert_eq!(&token.owner_id, owner.id());
+    assert_eq!(token.metadata, Some(metadata));
+    assert_eq!(nft.nft_total_supply().await?, 1);
+
+    // Alice gets approved, and transfers the token to herself on behalf of the owner.
+    nft.nft_approve(&owner, "0", alice.id(), None)
+        .await?
+        .into_result()?;
+    assert!(nft.nft_is_approved("0", alice.id(), None).await?);
+    let approval_id = nft
+        .nft_token("0")
+        .await?
+        .unwrap()
+        .approved_account_ids
+        .unwrap()[alice.id()];
+
+    let transferred = nft
+        .nft_transfer(&alice, alice.id(), "0", Some(approval_id), Some("mine"))
+        .await?
+        .into_result()?;
+    let transfers = transferred.transfers()?;
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(&transfers[0].old_owner_id, owner.id());
+    assert_eq!(&transfers[0].new_owner_id, alice.id());
+    assert_eq!(transfers[0].authorized_id.as_ref(), Some(alice.id()));
+    assert_eq!(nft.nft_supply_for_owner(alice.id()).await?, 1);
+    assert_eq!(nft.nft_supply_for_owner(owner.id()).await?, 0);
+
+    // Approvals are cleared on transfer.
+    assert!(!nft.nft_is_approved("0", alice.id(), None).await?);
+    nft.nft_approve(&alice, "0", bob.id(), None)
+        .await?
+        .into_result()?;
+    nft.nft_revoke(&alice, "0", bob.id()).await?.into_result()?;
+    assert!(!nft.nft_is_approved("0", bob.id(), None).await?);
+
+    // Bob has no contract to handle `nft_on_transfer`, so the token gets returned.
+    let result = nft
+        .nft_transfer_call(&alice, bob.id(), "0", None, None, "take it")
+        .await?;
+    assert!(result.is_success());
+    assert!(!result.is_transferred()?);
+
+    let tokens = nft.nft_tokens_for_owner(alice.id(), None, None).await?;
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token_id, "0");
+    assert_eq!(nft.nft_tokens(None, Some(10)).await?.len(), 1);
+
+    Ok(())
+}
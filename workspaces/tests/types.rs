@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use near_primitives::borsh::{self, BorshDeserialize};
 
-use near_workspaces::types::{KeyType, PublicKey, SecretKey};
+use near_workspaces::types::{KeyType, PublicKey, SecretKey, DEFAULT_HD_PATH};
 use near_workspaces::AccountId;
 
 fn default_workspaces_pubkey() -> anyhow::Result<PublicKey> {
@@ -40,6 +40,49 @@ fn test_keypair_secp256k1() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_seed_phrase() -> anyhow::Result<()> {
+    let (phrase, sk) = SecretKey::generate_seed_phrase();
+    assert_eq!(phrase.split_whitespace().count(), 12);
+    assert!(matches!(sk.key_type(), KeyType::ED25519));
+
+    // Derivation is deterministic for a phrase and path, and tolerates extra whitespace
+    // and uppercase letters.
+    let spaced = format!("  {}  ", phrase.replace(' ', " \t\n "));
+    assert_eq!(SecretKey::from_seed_phrase(&spaced, DEFAULT_HD_PATH)?, sk);
+    let uppercase = phrase.to_uppercase();
+    assert_eq!(
+        SecretKey::from_seed_phrase(&uppercase, DEFAULT_HD_PATH)?,
+        sk
+    );
+    assert_ne!(SecretKey::from_seed_phrase(&phrase, "m/44'/397'/1'")?, sk);
+
+    assert!(SecretKey::from_seed_phrase("not a valid seed phrase", DEFAULT_HD_PATH).is_err());
+    assert!(SecretKey::from_seed_phrase(&phrase, "not a path").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_seed_phrase_known_answer() -> anyhow::Result<()> {
+    // Known answer derived outside of this crate, following BIP-39 and SLIP-10 at the
+    // default path like near-seed-phrase does.
+    let sk = SecretKey::from_seed_phrase(
+        "shoot island position soft burden budget tooth cruel issue economy destroy above",
+        DEFAULT_HD_PATH,
+    )?;
+    assert_eq!(
+        sk.to_string(),
+        "ed25519:3jFpZEcbhcjpqVE27zU3d7WHcS7Wq716v5WryU8Tj4EaNTHTj8iAhtPW7KCdFV2fnjNf9toawUbdqZnhrRtLKe6w"
+    );
+    assert_eq!(
+        sk.public_key().to_string(),
+        "ed25519:r4yuiZE45mzeZAENDEF2pWeFBJkW8mQYGx3rU46zCqh"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_pubkey_serialization() -> anyhow::Result<()> {
     for key_type in [KeyType::ED25519, KeyType::SECP256K1] {
@@ -0,0 +1,103 @@
+use near_workspaces::result::UpgradeStep;
+use serde_json::json;
+use test_log::test;
+
+const STATUS_MSG_WASM_FILEPATH: &str = "../examples/res/status_message.wasm";
+
+#[test(tokio::test)]
+async fn test_upgrade_and_migrate() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(STATUS_MSG_WASM_FILEPATH)?;
+    let contract = worker.dev_deploy(&wasm).await?;
+    contract
+        .call("set_status")
+        .args_json(json!({ "message": "foo" }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Redeploying without a migration leaves the state alone.
+    let diff = contract.upgrade(&wasm).transact().await?.into_result()?;
+    assert!(diff.is_empty(), "{}", diff);
+
+    let diff = contract
+        .upgrade(&wasm)
+        .migrate("set_status", json!({ "message": "bar" }))
+        .transact()
+        .await?
+        .into_result()?;
+    assert!(diff.added().is_empty());
+    assert!(diff.removed().is_empty());
+    assert_eq!(diff.changed().len(), 1, "{}", diff);
+
+    let status: Option<String> = contract
+        .view("get_status")
+        .args_json(json!({ "account_id": contract.id() }))
+        .await?
+        .json()?;
+    assert_eq!(status.as_deref(), Some("bar"));
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_upgrade_failed_migration() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(STATUS_MSG_WASM_FILEPATH)?;
+    let contract = worker.dev_deploy(&wasm).await?;
+
+    // The migration panics on arguments it cannot deserialize.
+    let result = contract
+        .upgrade(&wasm)
+        .migrate("set_status", json!({ "status": "foo" }))
+        .transact()
+        .await?;
+    assert!(!result.is_success());
+    assert_eq!(result.failed_step(), Some(UpgradeStep::Migrate));
+    // The batch got reverted as a whole.
+    assert!(result.state_diff.is_empty());
+
+    let err = result.into_result().unwrap_err();
+    assert!(
+        err.to_string().contains("failed at the migrate step"),
+        "{}",
+        err
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_upgrade_to_invalid_wasm() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let wasm = std::fs::read(STATUS_MSG_WASM_FILEPATH)?;
+    let contract = worker.dev_deploy(&wasm).await?;
+
+    // Invalid code only fails once the migration calls into it and it gets compiled.
+    let result = contract
+        .upgrade(b"not a wasm module")
+        .migrate("migrate", json!({}))
+        .transact()
+        .await?;
+    assert!(!result.is_success());
+    assert_eq!(result.failed_step(), Some(UpgradeStep::Deploy));
+    assert!(result.state_diff.is_empty());
+    assert_eq!(contract.view_code().await?, wasm);
+
+    let err = result.into_result().unwrap_err();
+    assert!(
+        err.to_string().contains("failed at the deploy step"),
+        "{}",
+        err
+    );
+
+    // The new code missing the migration method is a broken deploy as well.
+    let result = contract
+        .upgrade(&wasm)
+        .migrate("migrate", json!({}))
+        .transact()
+        .await?;
+    assert_eq!(result.failed_step(), Some(UpgradeStep::Deploy));
+
+    Ok(())
+}